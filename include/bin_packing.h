@@ -0,0 +1,45 @@
+#ifndef BIN_PACKING_H
+#define BIN_PACKING_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdint.h>
+#include <stddef.h>
+
+/**
+ * A null pointer was passed.
+ */
+#define BIN_PACKING_ERROR_NULL -1
+
+/**
+ * The strategy name is not valid UTF-8 or does not name a known strategy.
+ */
+#define BIN_PACKING_ERROR_STRATEGY -2
+
+/**
+ * An item is larger than the bin capacity.
+ */
+#define BIN_PACKING_ERROR_TOO_LARGE -3
+
+
+
+/**
+ * Packs `len` items with the sizes in `sizes` into bins of the given capacity, using the
+ * strategy named by the null-terminated string `strategy`, such as `"ffd"`.
+ *
+ * Writes the index of the bin each item is packed into to the corresponding element of
+ * `assignment`, and returns the number of bins used, or one of the negative
+ * `BIN_PACKING_ERROR_*` codes, in which case `assignment` is left untouched.
+ *
+ * # Safety
+ *
+ * `sizes` and `assignment` must each point to `len` valid `u64`s, which may only be null if `len`
+ * is zero, and `strategy` must point to a null-terminated string.
+ */
+int64_t bin_packing_pack(const uint64_t *sizes,
+                         size_t len,
+                         uint64_t capacity,
+                         const char *strategy,
+                         uint64_t *assignment);
+
+#endif  /* BIN_PACKING_H */
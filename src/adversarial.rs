@@ -0,0 +1,173 @@
+//! Known worst-case item sequences for online and offline strategies.
+//!
+//! Random items rarely show how badly a strategy can do. The sequences here are the classic
+//! lower-bound constructions, which make the strategies they target use a known multiple of the
+//! fewest bins possible, to test strategies against adversarial inputs.
+
+use crate::eval::Instance;
+
+/// An item sequence built to make particular strategies perform badly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Adversarial {
+    /// The capacity and the sizes of the items, in the order to pack them.
+    pub instance: Instance,
+    /// The number of bins the targeted strategies use when packing the items in order.
+    pub bins: usize,
+    /// The fewest bins the items fit into.
+    pub optimum: usize,
+}
+
+impl Adversarial {
+    /// Returns how many times as many bins as necessary the targeted strategies use.
+    pub fn ratio(&self) -> f64 {
+        self.bins as f64 / self.optimum.max(1) as f64
+    }
+}
+
+/// Returns a sequence that makes [`crate::online::NextFit`] use close to twice the fewest bins
+/// possible, with `2n` pairs of a half-full item and a tiny one.
+///
+/// Next fit closes each bin after a pair, using `2n` bins, while the large items pair up and the
+/// tiny ones share a single bin, using `n + 1`.
+pub fn next_fit(n: usize) -> Adversarial {
+    let n = n.max(1);
+    Adversarial {
+        instance: Instance {
+            capacity: 2 * n,
+            sizes: (0..2 * n).flat_map(|_| [n, 1]).collect(),
+        },
+        bins: 2 * n,
+        optimum: n + 1,
+    }
+}
+
+/// Returns a sequence that makes [`crate::online::FirstFit`] and [`crate::online::BestFit`] use
+/// 5/3 of the fewest bins possible, with `6m` items each of just over a seventh, a third and half
+/// of the capacity, in that order.
+///
+/// Both strategies pack the items of each size among themselves, six, two and one per bin, using
+/// `10m` bins, while one item of each size fits into a bin together, using `6m`.
+pub fn first_fit(m: usize) -> Adversarial {
+    let m = m.max(1);
+    let sizes = [61, 141, 211]
+        .into_iter()
+        .flat_map(|size| std::iter::repeat_n(size, 6 * m))
+        .collect();
+    Adversarial {
+        instance: Instance {
+            capacity: 420,
+            sizes,
+        },
+        bins: 10 * m,
+        optimum: 6 * m,
+    }
+}
+
+/// Returns an instance that makes [`crate::offline::FirstFitDecreasing`] and
+/// [`crate::offline::BestFitDecreasing`] use 11/9 of the fewest bins possible, the published
+/// worst case of first fit decreasing.
+///
+/// There are `6m` items each of just over half, a quarter plus two and a quarter plus one
+/// hundredth of the capacity, and `12m` items just under a quarter. Both strategies pair the
+/// largest items with the next largest, leaving no room for the rest, which then fill bins of
+/// their own, using `11m` bins. Packed the other way round, the items fit into `9m`.
+pub fn first_fit_decreasing(m: usize) -> Adversarial {
+    let m = m.max(1);
+    let sizes = [(51, 6 * m), (27, 6 * m), (26, 6 * m), (23, 12 * m)]
+        .into_iter()
+        .flat_map(|(size, count)| std::iter::repeat_n(size, count))
+        .collect();
+    Adversarial {
+        instance: Instance {
+            capacity: 100,
+            sizes,
+        },
+        bins: 11 * m,
+        optimum: 9 * m,
+    }
+}
+
+/// Returns an instance that makes [`crate::offline::ModifiedFirstFitDecreasing`] use 7/6 of the
+/// fewest bins possible, with `6m` items each of 23, 17 and 16 sixtieths of the capacity.
+///
+/// Without items larger than half the capacity, modified first fit decreasing packs like first
+/// fit decreasing, which puts the items of each size among themselves, two, three and three per
+/// bin, using `7m` bins, while one item of each size fits into a bin together, using `6m`.
+pub fn modified_first_fit_decreasing(m: usize) -> Adversarial {
+    let m = m.max(1);
+    let sizes = [23, 17, 16]
+        .into_iter()
+        .flat_map(|size| std::iter::repeat_n(size, 6 * m))
+        .collect();
+    Adversarial {
+        instance: Instance {
+            capacity: 60,
+            sizes,
+        },
+        bins: 7 * m,
+        optimum: 6 * m,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+
+    /// Repeats each of the given bins `count` times.
+    fn bins(patterns: &[(&[usize], usize)]) -> Vec<Vec<usize>> {
+        patterns
+            .iter()
+            .flat_map(|&(bin, count)| std::iter::repeat_n(bin.to_vec(), count))
+            .collect()
+    }
+
+    #[test]
+    fn sequences_hit_their_bounds() {
+        for (sequence, algorithms, optimal) in [
+            (
+                next_fit(3),
+                &[Algorithm::NextFit][..],
+                bins(&[(&[3, 3], 3), (&[1; 6], 1)]),
+            ),
+            (
+                first_fit(1),
+                &[Algorithm::FirstFit, Algorithm::BestFit][..],
+                bins(&[(&[61, 141, 211], 6)]),
+            ),
+            (
+                first_fit_decreasing(1),
+                &[Algorithm::FirstFitDecreasing, Algorithm::BestFitDecreasing][..],
+                bins(&[(&[51, 26, 23], 6), (&[27, 27, 23, 23], 3)]),
+            ),
+            (
+                modified_first_fit_decreasing(1),
+                &[Algorithm::ModifiedFirstFitDecreasing][..],
+                bins(&[(&[23, 17, 16], 6)]),
+            ),
+        ] {
+            let Instance { capacity, sizes } = &sequence.instance;
+            for algorithm in algorithms {
+                assert_eq!(algorithm.pack(*capacity, sizes).len(), sequence.bins);
+            }
+
+            // The optimal packing holds every item, and no packing can use fewer bins than the
+            // total size needs.
+            assert!(optimal
+                .iter()
+                .all(|bin| bin.iter().sum::<usize>() <= *capacity));
+            let mut packed = optimal.concat();
+            let mut expected = sizes.clone();
+            packed.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(packed, expected);
+            assert_eq!(optimal.len(), sequence.optimum);
+            assert_eq!(
+                sizes.iter().sum::<usize>().div_ceil(*capacity),
+                sequence.optimum
+            );
+        }
+        assert!((first_fit(2).ratio() - 5.0 / 3.0).abs() < 1e-9);
+        assert!((first_fit_decreasing(2).ratio() - 11.0 / 9.0).abs() < 1e-9);
+    }
+}
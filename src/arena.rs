@@ -0,0 +1,188 @@
+//! Arena-backed bin storage with stable handles.
+//!
+//! A `Vec` of bins identifies bins by index, which changes whenever a bin before it is removed.
+//! An [`Arena`] keeps its bins in one contiguous allocation, so strategies scan them as a slice,
+//! but hands out a [`Handle`] for each bin that stays valid until the bin itself is removed.
+
+use crate::online::Strategy;
+use crate::*;
+
+/// Identifies a bin of an [`Arena`].
+///
+/// Handles are assigned in the order bins are opened and never reused, so the handle of a removed
+/// bin never refers to another bin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle(usize);
+
+/// Bins stored contiguously in the order they were opened, addressed by [`Handle`].
+pub struct Arena<B> {
+    bins: Vec<B>,
+    /// The handle of each bin, by index.
+    handles: Vec<Handle>,
+    /// The index of the bin with each handle, or `None` if it was removed.
+    indices: Vec<Option<usize>>,
+}
+
+impl<B> Default for Arena<B> {
+    fn default() -> Self {
+        Self {
+            bins: vec![],
+            handles: vec![],
+            indices: vec![],
+        }
+    }
+}
+
+impl<B: Bin> Arena<B> {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty arena with room for `bins` bins before it needs to allocate again.
+    pub fn with_capacity(bins: usize) -> Self {
+        Self {
+            bins: Vec::with_capacity(bins),
+            handles: Vec::with_capacity(bins),
+            indices: Vec::with_capacity(bins),
+        }
+    }
+
+    /// Returns all bins, in the order they were opened.
+    pub fn bins(&self) -> &[B] {
+        &self.bins
+    }
+
+    /// Returns the number of bins.
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    /// Returns whether the arena holds no bins.
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    /// Returns the bin with the given handle, or `None` if it was removed.
+    pub fn get(&self, handle: Handle) -> Option<&B> {
+        self.index_of(handle).map(|i| &self.bins[i])
+    }
+
+    /// Returns the bin with the given handle mutably, or `None` if it was removed.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut B> {
+        self.index_of(handle).map(|i| &mut self.bins[i])
+    }
+
+    /// Returns the current index of the bin with the given handle in [`Arena::bins`], or `None`
+    /// if it was removed.
+    pub fn index_of(&self, handle: Handle) -> Option<usize> {
+        self.indices.get(handle.0).copied().flatten()
+    }
+
+    /// Returns the handle of the bin at the given index in [`Arena::bins`].
+    pub fn handle(&self, index: usize) -> Option<Handle> {
+        self.handles.get(index).copied()
+    }
+
+    /// Adds a bin, returning its handle.
+    pub fn insert(&mut self, bin: B) -> Handle {
+        let handle = Handle(self.indices.len());
+        self.indices.push(Some(self.bins.len()));
+        self.handles.push(handle);
+        self.bins.push(bin);
+        handle
+    }
+
+    /// Packs an item into a bin chosen by an online strategy, opening a new bin if it finds no
+    /// suitable one, and returns the handle of the bin.
+    pub fn pack(&mut self, strategy: impl Strategy, item: impl Item) -> Handle {
+        let handle = match strategy.next_idx(&self.bins, &item) {
+            Some(i) => self.handles[i],
+            None => self.insert(B::default()),
+        };
+        if let Some(bin) = self.get_mut(handle) {
+            bin.pack(item);
+        }
+        handle
+    }
+
+    /// Removes the bin with the given handle and returns it, or `None` if it was removed already.
+    /// The bins after it move up by one index, but keep their handles.
+    pub fn remove(&mut self, handle: Handle) -> Option<B> {
+        let index = self.index_of(handle)?;
+        self.indices[handle.0] = None;
+        self.handles.remove(index);
+        for moved in &self.handles[index..] {
+            self.indices[moved.0] = self.indices[moved.0].map(|i| i - 1);
+        }
+        Some(self.bins.remove(index))
+    }
+
+    /// Removes all bins for which the predicate returns `false` and returns the handles of the
+    /// removed bins, keeping the rest in order.
+    pub fn retain(&mut self, mut keep: impl FnMut(&B) -> bool) -> Vec<Handle> {
+        let kept = self.bins.iter().map(&mut keep).collect::<Vec<_>>();
+        let mut flags = kept.iter();
+        self.bins.retain(|_| *flags.next().unwrap());
+        let mut removed = vec![];
+        let mut flags = kept.iter();
+        self.handles.retain(|&handle| {
+            let kept = *flags.next().unwrap();
+            if !kept {
+                removed.push(handle);
+            }
+            kept
+        });
+        for handle in &removed {
+            self.indices[handle.0] = None;
+        }
+        for (i, handle) in self.handles.iter().enumerate() {
+            self.indices[handle.0] = Some(i);
+        }
+        removed
+    }
+
+    /// Returns the handle and bin of every bin, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &B)> {
+        self.handles.iter().copied().zip(&self.bins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::online::FirstFit;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn handles_survive_removals() {
+        let mut arena = Arena::<BinImpl>::with_capacity(4);
+        let handles = [6, 6, 6, 3].map(|size| arena.pack(FirstFit, size));
+        assert_eq!(handles[3], handles[0]);
+        assert_eq!(arena.remove(handles[1]).map(|bin| bin.used), Some(6));
+        assert_eq!(arena.remove(handles[1]).map(|bin| bin.used), None);
+        assert_eq!(arena.index_of(handles[2]), Some(1));
+        assert_eq!(arena.get(handles[2]).map(|bin| bin.used), Some(6));
+
+        let removed = arena.retain(|bin| bin.used < 9);
+        assert_eq!(removed, [handles[0]]);
+        assert_eq!(arena.handle(0), Some(handles[2]));
+        assert_eq!(arena.pack(FirstFit, 4), handles[2]);
+        assert_eq!(arena.len(), 1);
+    }
+}
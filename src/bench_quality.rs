@@ -0,0 +1,190 @@
+//! Measuring the quality of packings across suites of instances, for tracking regressions.
+//!
+//! Unlike the criterion benchmarks, which measure how fast strategies are, this measures how many
+//! bins they need compared to the lower bound, and reports the results as JSON so they can be
+//! compared between runs, for example across upgrades of this crate.
+
+use std::fmt::Write as _;
+
+use crate::eval::{Evaluation, Instance};
+use crate::*;
+
+/// A named collection of named instances.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Suite {
+    /// The name of the suite.
+    pub name: String,
+    /// The name and instance of each member of the suite.
+    pub instances: Vec<(String, Instance)>,
+}
+
+/// A registered strategy, which packs an instance.
+type Pack = Box<dyn Fn(&Instance) -> Packing>;
+
+/// A set of strategies to run over a set of suites, see [`QualityBench::run`].
+#[derive(Default)]
+pub struct QualityBench {
+    strategies: Vec<(String, Pack)>,
+    suites: Vec<Suite>,
+}
+
+/// How one strategy did on one instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// The name of the suite.
+    pub suite: String,
+    /// The name of the instance.
+    pub instance: String,
+    /// The name of the strategy.
+    pub strategy: String,
+    /// How the packing compares to the lower bound.
+    pub evaluation: Evaluation,
+}
+
+impl QualityBench {
+    /// Creates a benchmark without any strategies or suites.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a strategy under the given name, as a function that packs an instance.
+    pub fn strategy(
+        mut self,
+        name: impl Into<String>,
+        pack: impl Fn(&Instance) -> Packing + 'static,
+    ) -> Self {
+        self.strategies.push((name.into(), Box::new(pack)));
+        self
+    }
+
+    /// Registers a built-in strategy under its short name.
+    pub fn algorithm(self, algorithm: Algorithm) -> Self {
+        self.strategy(algorithm.name(), move |instance| {
+            algorithm.pack(instance.capacity, &instance.sizes)
+        })
+    }
+
+    /// Adds a suite of instances to run every strategy on.
+    pub fn suite(mut self, suite: Suite) -> Self {
+        self.suites.push(suite);
+        self
+    }
+
+    /// Packs every instance of every suite with every strategy, and returns the results in that
+    /// order.
+    pub fn run(&self) -> Vec<Record> {
+        let mut records = vec![];
+        for suite in &self.suites {
+            for (name, instance) in &suite.instances {
+                for (strategy, pack) in &self.strategies {
+                    records.push(Record {
+                        suite: suite.name.clone(),
+                        instance: name.clone(),
+                        strategy: strategy.clone(),
+                        evaluation: Evaluation::of(&pack(instance)),
+                    });
+                }
+            }
+        }
+        records
+    }
+}
+
+/// Formats records as a JSON array with one object per record.
+pub fn to_json(records: &[Record]) -> String {
+    let mut out = String::from("[");
+    for (i, record) in records.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"suite\":{},\"instance\":{},\"strategy\":{},\"bins\":{},\"lower_bound\":{},\
+             \"ratio\":{}}}",
+            string(&record.suite),
+            string(&record.instance),
+            string(&record.strategy),
+            record.evaluation.bins,
+            record.evaluation.lower_bound,
+            number(record.evaluation.ratio),
+        );
+    }
+    out.push(']');
+    out
+}
+
+/// Formats a string as a JSON string literal.
+fn string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Formats a number as JSON, which has no representation for infinity.
+fn number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_strategy_on_every_instance() {
+        let suite = Suite {
+            name: "small".to_string(),
+            instances: vec![
+                (
+                    "tricky \"ffd\"".to_string(),
+                    Instance {
+                        capacity: 10,
+                        sizes: vec![4, 4, 3, 3, 3, 3],
+                    },
+                ),
+                (
+                    "easy".to_string(),
+                    Instance {
+                        capacity: 10,
+                        sizes: vec![5, 5],
+                    },
+                ),
+            ],
+        };
+        let records = QualityBench::new()
+            .algorithm(Algorithm::FirstFitDecreasing)
+            .strategy("exact", |instance| {
+                let solution = exact::BranchAndBound::default().improve(
+                    &Algorithm::FirstFitDecreasing.pack(instance.capacity, &instance.sizes),
+                );
+                let mut bins = vec![vec![]; solution.bins];
+                for (i, &bin) in solution.assignment.iter().enumerate() {
+                    bins[bin].push(i);
+                }
+                Packing::from_bins(instance.capacity, instance.sizes.clone(), bins).unwrap()
+            })
+            .suite(suite)
+            .run();
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            to_json(&records[..2]),
+            "[{\"suite\":\"small\",\"instance\":\"tricky \\\"ffd\\\"\",\"strategy\":\"ffd\",\
+             \"bins\":3,\"lower_bound\":2,\"ratio\":1.5},\
+             {\"suite\":\"small\",\"instance\":\"tricky \\\"ffd\\\"\",\"strategy\":\"exact\",\
+             \"bins\":2,\"lower_bound\":2,\"ratio\":1}]"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Lower bounds on the number of bins needed to pack a set of items.
+
+use crate::*;
+
+/// Returns the lower bound on the number of bins given by the total size of all items, also known
+/// as L1.
+pub fn l1(capacity: usize, items: &[impl Item]) -> usize {
+    let total = items.iter().map(Item::size).sum::<usize>();
+    total.div_ceil(capacity.max(1))
+}
+
+/// Returns the lower bound on the number of bins of Martello and Toth, also known as L2, which is
+/// never below [`l1`].
+///
+/// For every threshold `k` up to half the capacity, items larger than half the capacity each need
+/// their own bin, and items of at least `k` only fit alongside them where there is room, which
+/// gives a bound that is much tighter than L1 when many items are large.
+pub fn l2(capacity: usize, items: &[impl Item]) -> usize {
+    let mut sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    let half = capacity / 2;
+
+    let mut best = l1(capacity, items);
+    let mut thresholds = sizes
+        .iter()
+        .copied()
+        .filter(|&s| s <= half)
+        .collect::<Vec<_>>();
+    thresholds.dedup();
+    for k in thresholds.into_iter().chain([0]) {
+        // Items that cannot share a bin with anything of size k or more.
+        let alone = sizes.iter().filter(|&&s| s > capacity - k).count();
+        let (large, large_total) = sizes
+            .iter()
+            .filter(|&&s| s <= capacity - k && s > half)
+            .fold((0, 0), |(n, total), &s| (n + 1, total + s));
+        let medium = sizes
+            .iter()
+            .filter(|&&s| s <= half && s >= k)
+            .sum::<usize>();
+        let room = large * capacity - large_total;
+        let extra = medium.saturating_sub(room).div_ceil(capacity.max(1));
+        best = best.max(alone + large + extra);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l1_rounds_up() {
+        assert_eq!(l1(10, &[6, 5]), 2);
+        assert_eq!(l1(10, &[5, 5]), 1);
+        assert_eq!(l1(10, &[] as &[usize]), 0);
+    }
+
+    #[test]
+    fn l2_counts_large_items() {
+        assert_eq!(l1(10, &[6, 6, 6]), 2);
+        assert_eq!(l2(10, &[6, 6, 6]), 3);
+        assert_eq!(l2(10, &[6, 6, 5, 5]), 3);
+        assert_eq!(l2(10, &[4, 4, 3, 3, 3, 3]), 2);
+        assert_eq!(l2(10, &[] as &[usize]), 0);
+    }
+}
@@ -0,0 +1,178 @@
+//! Certificates that let anyone check a packing without trusting whoever computed it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::*;
+
+/// A self-contained description of a packing: the capacity, the number of bins, the size of each
+/// item and the bin each item is packed into.
+///
+/// Certificates are written as `capacity;bins;sizes;assignment`, with the sizes and the assignment
+/// as comma-separated lists, for example `10;2;6,4,5;0,0,1`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Certificate {
+    /// The capacity of each bin.
+    pub capacity: usize,
+    /// The number of bins used.
+    pub bins: usize,
+    /// The size of each item.
+    pub sizes: Vec<usize>,
+    /// The index of the bin each item is packed into.
+    pub assignment: Vec<usize>,
+}
+
+/// The reason a certificate is invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CertificateError {
+    /// The certificate is not in the expected format.
+    Malformed,
+    /// There are not as many bin indices as there are items.
+    WrongLength,
+    /// An item is packed into a bin beyond the number of bins.
+    NoSuchBin {
+        /// The index of the item.
+        item: usize,
+    },
+    /// The items in a bin add up to more than the capacity.
+    Overfull {
+        /// The index of the bin.
+        bin: usize,
+    },
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateError::Malformed => f.write_str("malformed certificate"),
+            CertificateError::WrongLength => {
+                f.write_str("the number of items and bin indices differ")
+            }
+            CertificateError::NoSuchBin { item } => {
+                write!(f, "item {item} is packed into a bin that does not exist")
+            }
+            CertificateError::Overfull { bin } => write!(f, "bin {bin} is overfull"),
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl Certificate {
+    /// Checks that the certificate describes a valid packing, with every item in one of the bins
+    /// and no bin overfull.
+    pub fn verify(&self) -> Result<(), CertificateError> {
+        if self.sizes.len() != self.assignment.len() {
+            return Err(CertificateError::WrongLength);
+        }
+        let mut loads = vec![0usize; self.bins];
+        for (item, (&size, &bin)) in self.sizes.iter().zip(&self.assignment).enumerate() {
+            let load = loads
+                .get_mut(bin)
+                .ok_or(CertificateError::NoSuchBin { item })?;
+            *load = load
+                .checked_add(size)
+                .filter(|&load| load <= self.capacity)
+                .ok_or(CertificateError::Overfull { bin })?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&Packing> for Certificate {
+    fn from(packing: &Packing) -> Self {
+        Self {
+            capacity: packing.capacity(),
+            bins: packing.len(),
+            sizes: packing.sizes().to_vec(),
+            assignment: packing.assignment(),
+        }
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |values: &[usize]| {
+            values
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(
+            f,
+            "{};{};{};{}",
+            self.capacity,
+            self.bins,
+            list(&self.sizes),
+            list(&self.assignment)
+        )
+    }
+}
+
+impl FromStr for Certificate {
+    type Err = CertificateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .map_err(|_| CertificateError::Malformed)
+        };
+        let list = |s: &str| {
+            if s.trim().is_empty() {
+                Ok(vec![])
+            } else {
+                s.split(',').map(number).collect()
+            }
+        };
+        let parts = s.split(';').collect::<Vec<_>>();
+        let [capacity, bins, sizes, assignment] = parts[..] else {
+            return Err(CertificateError::Malformed);
+        };
+        Ok(Self {
+            capacity: number(capacity)?,
+            bins: number(bins)?,
+            sizes: list(sizes)?,
+            assignment: list(assignment)?,
+        })
+    }
+}
+
+/// Parses and verifies a certificate, returning it if it describes a valid packing.
+pub fn verify_certificate(certificate: &str) -> Result<Certificate, CertificateError> {
+    let certificate = certificate.parse::<Certificate>()?;
+    certificate.verify()?;
+    Ok(certificate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_verifies() {
+        let packing = Algorithm::NextFit.pack(10, &[6, 4, 5]);
+        let certificate = Certificate::from(&packing).to_string();
+        assert_eq!(certificate, "10;2;6,4,5;0,0,1");
+        assert_eq!(verify_certificate(&certificate).map(|c| c.bins), Ok(2));
+        assert_eq!(verify_certificate("0;0;;"), Ok(Certificate::default()));
+
+        assert_eq!(
+            verify_certificate("10;1;6,4,5;0,0,0"),
+            Err(CertificateError::Overfull { bin: 0 })
+        );
+        assert_eq!(
+            verify_certificate("10;1;6,4,5;0,0,1"),
+            Err(CertificateError::NoSuchBin { item: 2 })
+        );
+        assert_eq!(
+            verify_certificate("10;2;6,4,5;0,0"),
+            Err(CertificateError::WrongLength)
+        );
+        assert_eq!(
+            verify_certificate("10;2;6,x;0,0"),
+            Err(CertificateError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Hard constraints on which items may share a bin, assembled with a builder and applied to any
+//! online strategy, for example
+//! `Constraints::new().max_items(5).conflicts([(1, 2)]).spread_by(zone, 2).apply(BestFit)`.
+
+use std::cell::{RefCell, RefMut};
+use std::collections::{HashMap, HashSet};
+
+use crate::online::{capacity, mask, relabel, Strategy, Verdict};
+use crate::*;
+
+type KeyFn = Box<dyn Fn(&dyn Item) -> Option<usize>>;
+
+/// A set of constraints on which items may share a bin.
+///
+/// Constraints that involve particular items identify them by [`Item::id`], and items without an
+/// id are not subject to them.
+#[derive(Default)]
+pub struct Constraints {
+    max_items: Option<usize>,
+    conflicts: HashMap<usize, HashSet<usize>>,
+    spreads: Vec<(KeyFn, usize)>,
+    balances: Vec<(KeyFn, usize)>,
+}
+
+impl Constraints {
+    /// Creates an empty set of constraints, which allows anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows at most `max` items in each bin.
+    pub fn max_items(mut self, max: usize) -> Self {
+        self.max_items = Some(max);
+        self
+    }
+
+    /// Keeps the items with the ids of each pair out of each other's bins.
+    pub fn conflicts(mut self, pairs: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        for (a, b) in pairs {
+            self.conflicts.entry(a).or_default().insert(b);
+            self.conflicts.entry(b).or_default().insert(a);
+        }
+        self
+    }
+
+    /// Allows at most `max` items with the same key in each bin, such as replicas of the same
+    /// service, which are then spread over several bins. Items without a key are not limited.
+    pub fn spread_by(
+        mut self,
+        key: impl Fn(&dyn Item) -> Option<usize> + 'static,
+        max: usize,
+    ) -> Self {
+        self.spreads.push((Box::new(key), max));
+        self
+    }
+
+    /// Allows items with the same key to take up at most `percent` percent of the capacity of each
+    /// bin by size, such as products of one kind on a pallet, so that every bin holds a mix of
+    /// keys. Items without a key are not limited.
+    ///
+    /// Unlike [`Constraints::spread_by`], this weighs items by their size rather than counting
+    /// them.
+    pub fn balance_by(
+        mut self,
+        key: impl Fn(&dyn Item) -> Option<usize> + 'static,
+        percent: usize,
+    ) -> Self {
+        self.balances.push((Box::new(key), percent));
+        self
+    }
+
+    /// Applies the constraints to an online strategy.
+    pub fn apply<S: Strategy>(self, strategy: S) -> Constrained<S> {
+        Constrained {
+            constraints: self,
+            strategy,
+            contents: RefCell::default(),
+            pending: RefCell::default(),
+        }
+    }
+
+    /// Returns the name of the first constraint that keeps the item out of a bin of the given
+    /// capacity with the given contents, or `None` if it may go in.
+    fn violation(
+        &self,
+        contents: &Contents,
+        capacity: usize,
+        item: &dyn Item,
+    ) -> Option<&'static str> {
+        if self.max_items.is_some_and(|max| contents.items >= max) {
+            return Some("max items");
+        }
+        if let Some(conflicts) = item.id().and_then(|id| self.conflicts.get(&id)) {
+            if contents.ids.iter().any(|id| conflicts.contains(id)) {
+                return Some("conflict");
+            }
+        }
+        for (i, (key, max)) in self.spreads.iter().enumerate() {
+            let count = |key| {
+                contents
+                    .keys
+                    .get(i)
+                    .and_then(|keys| keys.get(&key))
+                    .copied()
+            };
+            if key(item).and_then(count).is_some_and(|n| n >= *max) {
+                return Some("spread");
+            }
+        }
+        for (i, (key, percent)) in self.balances.iter().enumerate() {
+            let share = |key| {
+                let size = contents.shares.get(i).and_then(|shares| shares.get(&key));
+                size.copied().unwrap_or(0) + item.size()
+            };
+            if key(item)
+                .map(share)
+                .is_some_and(|share| share * 100 > percent * capacity)
+            {
+                return Some("balance");
+            }
+        }
+        None
+    }
+
+    /// Adds the item to the contents of a bin.
+    fn add(&self, contents: &mut Contents, item: &dyn Item) {
+        contents.items += 1;
+        contents.ids.extend(item.id());
+        contents.keys.resize_with(self.spreads.len(), HashMap::new);
+        for (i, (key, _)) in self.spreads.iter().enumerate() {
+            if let Some(key) = key(item) {
+                *contents.keys[i].entry(key).or_default() += 1;
+            }
+        }
+        contents
+            .shares
+            .resize_with(self.balances.len(), HashMap::new);
+        for (i, (key, _)) in self.balances.iter().enumerate() {
+            if let Some(key) = key(item) {
+                *contents.shares[i].entry(key).or_default() += item.size();
+            }
+        }
+    }
+}
+
+/// What a [`Constrained`] strategy packed into a bin.
+#[derive(Clone, Default)]
+struct Contents {
+    items: usize,
+    ids: Vec<usize>,
+    /// How many items with each key there are, for each spread constraint.
+    keys: Vec<HashMap<usize, usize>>,
+    /// The total size of the items with each key, for each balance constraint.
+    shares: Vec<HashMap<usize, usize>>,
+}
+
+/// An online strategy that only packs items into bins the [`Constraints`] allow, choosing among
+/// them with the wrapped strategy.
+///
+/// The wrapped strategy sees bins that are ruled out as full. The strategy keeps track of what it
+/// packed, assuming every item goes where it says, so it should be used with the same bins
+/// throughout.
+pub struct Constrained<S> {
+    constraints: Constraints,
+    strategy: S,
+    contents: RefCell<Vec<Contents>>,
+    /// The contents of the bin opened for the last item, which had no bin to go into.
+    pending: RefCell<Option<Contents>>,
+}
+
+impl<S> Constrained<S> {
+    /// Returns the name of the first constraint that keeps the item out of the bin of type `B`
+    /// with the given index, or `None` if it may go in.
+    pub fn violation<B: Bin>(&self, bin: usize, item: &impl Item) -> Option<&'static str> {
+        let contents = self.contents.borrow();
+        let contents = contents.get(bin).cloned().unwrap_or_default();
+        self.constraints.violation(&contents, B::capacity(), item)
+    }
+}
+
+impl<S: Strategy> Constrained<S> {
+    /// Catches up with the bins opened since the last item, returning the contents of each bin.
+    fn sync(&self, bins: usize) -> RefMut<'_, Vec<Contents>> {
+        let mut contents = self.contents.borrow_mut();
+        contents.truncate(bins);
+        if bins > contents.len() {
+            contents.push(self.pending.take().unwrap_or_default());
+            contents.resize(bins, Contents::default());
+        }
+        contents
+    }
+}
+
+impl<S: Strategy> Strategy for Constrained<S> {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let mut contents = self.sync(bins.len());
+        let views = mask(bins, item, |i| {
+            let capacity = capacity(bins);
+            self.constraints
+                .violation(&contents[i], capacity, item)
+                .is_some()
+        });
+        let idx = self.strategy.next_idx(&views, item);
+        match idx {
+            Some(i) => self.constraints.add(&mut contents[i], item),
+            None => {
+                let mut opened = Contents::default();
+                self.constraints.add(&mut opened, item);
+                self.pending.replace(Some(opened));
+            }
+        }
+        idx
+    }
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        let contents = self.sync(bins.len());
+        let violations = contents
+            .iter()
+            .map(|contents| self.constraints.violation(contents, capacity(bins), item))
+            .collect::<Vec<_>>();
+        let views = mask(bins, item, |i| violations[i].is_some());
+        relabel(self.strategy.explain(&views, item), |i| violations[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::online::FirstFit;
+
+    #[derive(Default)]
+    struct BinImpl {
+        ids: Vec<usize>,
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.ids.extend(item.id());
+            self.used += item.size();
+        }
+    }
+
+    struct Replica {
+        id: usize,
+        service: usize,
+    }
+    impl Item for Replica {
+        fn size(&self) -> usize {
+            1
+        }
+        fn id(&self) -> Option<usize> {
+            Some(self.id)
+        }
+    }
+
+    struct Sized {
+        id: usize,
+        size: usize,
+    }
+    impl Item for Sized {
+        fn size(&self) -> usize {
+            self.size
+        }
+        fn id(&self) -> Option<usize> {
+            Some(self.id)
+        }
+    }
+
+    #[test]
+    fn constraints_rule_out_bins() {
+        let items = [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 3)]
+            .map(|(id, service)| Replica { id, service });
+        let services = items.iter().map(|r| r.service).collect::<Vec<_>>();
+        let strategy = Constraints::new()
+            .max_items(3)
+            .conflicts([(2, 4)])
+            .spread_by(move |item| item.id().map(|id| services[id]), 1)
+            .apply(FirstFit);
+        assert_eq!(strategy.violation::<BinImpl>(0, &5), None);
+        let bins = online::pack::<BinImpl>(&strategy, items);
+        let ids = bins.iter().map(|b| b.ids.clone()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![vec![0, 2, 5], vec![1, 3, 4]]);
+        assert_eq!(
+            strategy.violation::<BinImpl>(0, &Replica { id: 4, service: 9 }),
+            Some("max items")
+        );
+    }
+
+    #[test]
+    fn balance_limits_the_share_of_each_key() {
+        let kinds = [0, 0, 0, 1, 1];
+        let strategy = Constraints::new()
+            .balance_by(move |item| item.id().map(|id| kinds[id]), 50)
+            .apply(FirstFit);
+        let items = (0..5).map(|id| Sized { id, size: 3 });
+        let bins = online::pack::<BinImpl>(&strategy, items);
+        let ids = bins.iter().map(|b| b.ids.clone()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![vec![0, 3], vec![1, 4], vec![2]]);
+        assert_eq!(
+            strategy.violation::<BinImpl>(2, &Sized { id: 1, size: 3 }),
+            Some("balance")
+        );
+    }
+}
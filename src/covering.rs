@@ -0,0 +1,129 @@
+//! Machine covering: splitting items into a fixed number of bins so that the least loaded bin
+//! holds as much as possible.
+//!
+//! This is the dual of minimizing the largest load, useful to share work out so that no worker
+//! is starved. The bins have no capacity, and every item is placed.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Item;
+
+/// Items split into a fixed number of bins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Covering {
+    /// The indices of the items in each bin.
+    pub bins: Vec<Vec<usize>>,
+    /// The total size of the items in each bin.
+    pub loads: Vec<usize>,
+}
+
+impl Covering {
+    /// Returns the load of the least loaded bin, zero if there are no bins.
+    pub fn min_load(&self) -> usize {
+        self.loads.iter().copied().min().unwrap_or(0)
+    }
+}
+
+/// Places the items largest first into the least loaded of `bins` bins, breaking ties by bin
+/// index.
+///
+/// With no bins, no items are placed.
+pub fn greedy(bins: usize, items: &[impl Item]) -> Covering {
+    let mut covering = Covering {
+        bins: vec![vec![]; bins],
+        loads: vec![0; bins],
+    };
+    if bins == 0 {
+        return covering;
+    }
+    let mut least = (0..bins)
+        .map(|b| Reverse((0, b)))
+        .collect::<BinaryHeap<_>>();
+    for i in largest_first(items) {
+        let Reverse((load, b)) = least.pop().unwrap();
+        covering.bins[b].push(i);
+        covering.loads[b] = load + items[i].size();
+        least.push(Reverse((covering.loads[b], b)));
+    }
+    covering
+}
+
+/// Splits the items into `bins` bins with the largest differencing method of Karmarkar and Karp.
+///
+/// Each item starts out as a split of its own, with the item in one bin and the others empty.
+/// The two splits whose loads differ the most are then repeatedly merged, joining the most loaded
+/// bin of one with the least loaded of the other, until one split is left. This usually evens out
+/// the loads much better than [`greedy`], but not always.
+///
+/// With no bins, no items are placed.
+pub fn differencing(bins: usize, items: &[impl Item]) -> Covering {
+    if bins == 0 {
+        return greedy(0, items);
+    }
+    // Each split keeps its bins ordered from most to least loaded.
+    let mut splits = vec![];
+    let mut widest = BinaryHeap::new();
+    for i in largest_first(items) {
+        let mut split = vec![(0, vec![]); bins];
+        split[0] = (items[i].size(), vec![i]);
+        widest.push((items[i].size(), Reverse(splits.len())));
+        splits.push(split);
+    }
+    let mut last = None;
+    while let Some((_, Reverse(a))) = widest.pop() {
+        let Some((_, Reverse(b))) = widest.pop() else {
+            last = Some(a);
+            break;
+        };
+        let first = std::mem::take(&mut splits[a]);
+        let second = std::mem::take(&mut splits[b]);
+        let mut merged = first
+            .into_iter()
+            .zip(second.into_iter().rev())
+            .map(|((load, mut items), (other, rest))| {
+                items.extend(rest);
+                (load + other, items)
+            })
+            .collect::<Vec<_>>();
+        merged.sort_by_key(|&(load, _)| Reverse(load));
+        widest.push((merged[0].0 - merged[bins - 1].0, Reverse(splits.len())));
+        splits.push(merged);
+    }
+    let split = last.map_or_else(
+        || vec![(0, vec![]); bins],
+        |a| std::mem::take(&mut splits[a]),
+    );
+    let (loads, bins) = split.into_iter().unzip();
+    Covering { bins, loads }
+}
+
+/// Returns the indices of the items, largest first and in order among equal sizes.
+fn largest_first(items: &[impl Item]) -> Vec<usize> {
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(items[i].size()));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differencing_covers_better_than_greedy() {
+        let sizes = [8, 7, 6, 5, 4];
+        let covering = greedy(2, &sizes);
+        assert_eq!(covering.bins, [vec![0, 3, 4], vec![1, 2]]);
+        assert_eq!(covering.min_load(), 13);
+
+        let covering = differencing(2, &sizes);
+        assert_eq!(covering.loads, [16, 14]);
+        assert_eq!(covering.min_load(), 14);
+        let mut placed = covering.bins.concat();
+        placed.sort_unstable();
+        assert_eq!(placed, [0, 1, 2, 3, 4]);
+
+        assert_eq!(differencing(3, &[0; 0]).loads, [0, 0, 0]);
+        assert_eq!(differencing(0, &sizes).min_load(), 0);
+    }
+}
@@ -0,0 +1,54 @@
+//! Graphviz export of packings, for looking at which items ended up together.
+
+use std::fmt::Write as _;
+
+use crate::Packing;
+
+/// Renders a packing as a Graphviz DOT graph, with a node for each bin labelled with its load, and
+/// a node for each item labelled with its index and size, connected to the bin it is packed into.
+///
+/// Render the output with `dot -Tsvg`, for example.
+pub fn to_dot(packing: &Packing) -> String {
+    let mut out = String::from("graph packing {\n    rankdir=LR;\n");
+    for (i, (bin, load)) in packing.bins().iter().zip(packing.loads()).enumerate() {
+        let _ = writeln!(
+            out,
+            "    bin{i} [shape=box, label=\"bin {i}\\n{load}/{}\"];",
+            packing.capacity()
+        );
+        for &item in bin {
+            let _ = writeln!(
+                out,
+                "    item{item} [label=\"item {item}\\nsize {}\"];",
+                packing.sizes()[item]
+            );
+            let _ = writeln!(out, "    bin{i} -- item{item};");
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+
+    #[test]
+    fn renders_bins_and_items() {
+        let packing = Algorithm::NextFit.pack(10, &[6, 5]);
+        assert_eq!(
+            to_dot(&packing),
+            "graph packing {
+    rankdir=LR;
+    bin0 [shape=box, label=\"bin 0\\n6/10\"];
+    item0 [label=\"item 0\\nsize 6\"];
+    bin0 -- item0;
+    bin1 [shape=box, label=\"bin 1\\n5/10\"];
+    item1 [label=\"item 1\\nsize 5\"];
+    bin1 -- item1;
+}
+"
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Evaluating how close strategies get to the fewest bins possible.
+
+use std::fmt;
+
+use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::*;
+
+/// A bin packing problem: items of the given sizes to be packed into bins of the given capacity.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Instance {
+    /// The capacity of each bin.
+    pub capacity: usize,
+    /// The size of each item.
+    pub sizes: Vec<usize>,
+}
+
+/// How well a packing does compared to the lower bound on the number of bins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Evaluation {
+    /// The number of bins used.
+    pub bins: usize,
+    /// The [`bounds::l2`] lower bound on the number of bins needed.
+    pub lower_bound: usize,
+    /// The number of bins used divided by the lower bound, which is at least the ratio to an
+    /// optimal packing. Both being zero counts as a ratio of one.
+    pub ratio: f64,
+}
+
+impl Evaluation {
+    /// Evaluates an existing packing, for example one made with an online strategy.
+    pub fn of(packing: &Packing) -> Self {
+        let lower_bound = bounds::l2(packing.capacity(), packing.sizes());
+        let ratio = if lower_bound == 0 {
+            if packing.is_empty() {
+                1.0
+            } else {
+                f64::INFINITY
+            }
+        } else {
+            packing.len() as f64 / lower_bound as f64
+        };
+        Self {
+            bins: packing.len(),
+            lower_bound,
+            ratio,
+        }
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bins, lower bound {}, ratio {:.3}",
+            self.bins, self.lower_bound, self.ratio
+        )
+    }
+}
+
+/// Packs an instance with an offline strategy and evaluates the result.
+pub fn evaluate(strategy: &impl offline::Strategy, instance: &Instance) -> Evaluation {
+    Evaluation::of(&Packing::new(strategy, instance.capacity, &instance.sizes))
+}
+
+/// Settings for comparing strategies on random instances, see [`MonteCarlo::run`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MonteCarlo {
+    /// The capacity of each bin.
+    pub capacity: usize,
+    /// The number of items in each instance.
+    pub items: usize,
+    /// The number of instances to pack.
+    pub trials: usize,
+    /// The seed from which all instances are generated.
+    pub seed: u64,
+}
+
+/// Statistics about how one strategy did over the trials of a [`MonteCarlo`] run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    /// The strategy.
+    pub algorithm: Algorithm,
+    /// The mean number of bins used.
+    pub mean_bins: f64,
+    /// The median number of bins used.
+    pub median_bins: usize,
+    /// The number of bins that 95% of the trials needed at most.
+    pub p95_bins: usize,
+    /// The most bins used in any trial.
+    pub max_bins: usize,
+    /// The mean utilization of the bins, see [`Packing::utilization`].
+    pub mean_utilization: f64,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: mean {:.2} bins, median {}, p95 {}, max {}, utilization {:.1}%",
+            self.algorithm,
+            self.mean_bins,
+            self.median_bins,
+            self.p95_bins,
+            self.max_bins,
+            self.mean_utilization * 100.0
+        )
+    }
+}
+
+impl MonteCarlo {
+    /// Packs random instances with each strategy and summarizes the results, in the order of the
+    /// strategies.
+    ///
+    /// Item sizes are drawn from the given distribution and capped at the capacity. Every
+    /// strategy packs the same instances, and the same settings always generate the same
+    /// instances.
+    pub fn run(&self, algorithms: &[Algorithm], sizes: impl Distribution<usize>) -> Vec<Summary> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut bins = vec![Vec::with_capacity(self.trials); algorithms.len()];
+        let mut utilization = vec![0.0; algorithms.len()];
+        for _ in 0..self.trials {
+            let instance = (&sizes)
+                .sample_iter(&mut rng)
+                .take(self.items)
+                .map(|size| size.min(self.capacity))
+                .collect::<Vec<_>>();
+            for (a, algorithm) in algorithms.iter().enumerate() {
+                let packing = algorithm.pack(self.capacity, &instance);
+                bins[a].push(packing.len());
+                utilization[a] += packing.utilization();
+            }
+        }
+
+        let trials = self.trials.max(1) as f64;
+        algorithms
+            .iter()
+            .zip(bins)
+            .zip(utilization)
+            .map(|((&algorithm, mut bins), utilization)| {
+                bins.sort_unstable();
+                Summary {
+                    algorithm,
+                    mean_bins: bins.iter().sum::<usize>() as f64 / trials,
+                    median_bins: percentile(&bins, 0.5),
+                    p95_bins: percentile(&bins, 0.95),
+                    max_bins: bins.last().copied().unwrap_or(0),
+                    mean_utilization: utilization / trials,
+                }
+            })
+            .collect()
+    }
+}
+
+/// An endless supply of random feasible packings of an instance, for Monte Carlo analyses of
+/// measures that depend on the layout of the items rather than the number of bins.
+///
+/// The packings are not meant to be good. Each goes through the items in a random order and puts
+/// each item into one of the bins it fits into or a new bin, with equal probability. Items larger
+/// than the capacity get a bin of their own.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    instance: Instance,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Creates a sampler of packings of the instance, which draws the same packings for the same
+    /// seed.
+    pub fn new(instance: Instance, seed: u64) -> Self {
+        Self {
+            instance,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Iterator for Sampler {
+    type Item = Packing;
+
+    fn next(&mut self) -> Option<Packing> {
+        let Instance { capacity, sizes } = &self.instance;
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.shuffle(&mut self.rng);
+        let mut bins = Vec::<Vec<usize>>::new();
+        let mut loads = vec![];
+        for i in order {
+            let open = (0..bins.len())
+                .filter(|&b| loads[b] + sizes[i] <= *capacity)
+                .collect::<Vec<_>>();
+            let choice = self.rng.gen_range(0..=open.len());
+            let b = open.get(choice).copied().unwrap_or_else(|| {
+                bins.push(vec![]);
+                loads.push(0);
+                bins.len() - 1
+            });
+            bins[b].push(i);
+            loads[b] += sizes[i];
+        }
+        Some(Packing::relabeled(*capacity, sizes.clone(), bins))
+    }
+}
+
+/// Returns the smallest value that at least the given fraction of the sorted values are at most.
+fn percentile(sorted: &[usize], fraction: f64) -> usize {
+    let rank = (fraction * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::FirstFitDecreasing;
+
+    #[test]
+    fn compares_bins_to_lower_bound() {
+        let instance = Instance {
+            capacity: 10,
+            sizes: vec![4, 4, 3, 3, 3, 3],
+        };
+        let evaluation = evaluate(&FirstFitDecreasing, &instance);
+        assert_eq!(
+            evaluation,
+            Evaluation {
+                bins: 3,
+                lower_bound: 2,
+                ratio: 1.5,
+            }
+        );
+        assert_eq!(evaluation.to_string(), "3 bins, lower bound 2, ratio 1.500");
+        assert_eq!(
+            evaluate(&FirstFitDecreasing, &Instance::default()).ratio,
+            1.0
+        );
+    }
+
+    #[test]
+    fn monte_carlo_is_reproducible() {
+        let settings = MonteCarlo {
+            capacity: 10,
+            items: 50,
+            trials: 20,
+            seed: 7,
+        };
+        let algorithms = [Algorithm::NextFit, Algorithm::FirstFitDecreasing];
+        let sizes = rand::distributions::Uniform::new_inclusive(1, 10);
+        let summaries = settings.run(&algorithms, sizes);
+        assert_eq!(summaries, settings.run(&algorithms, sizes));
+        assert_eq!(summaries[0].algorithm, Algorithm::NextFit);
+        assert!(summaries[1].mean_bins <= summaries[0].mean_bins);
+        assert!(summaries[1].median_bins <= summaries[1].p95_bins);
+        assert!(summaries[1].p95_bins <= summaries[1].max_bins);
+
+        let summaries = settings.run(&algorithms[1..], rand::distributions::Uniform::new(5, 6));
+        assert_eq!(summaries[0].mean_bins, 25.0);
+        assert_eq!(summaries[0].max_bins, 25);
+        assert_eq!(summaries[0].mean_utilization, 1.0);
+        assert_eq!(
+            summaries[0].to_string(),
+            "ffd: mean 25.00 bins, median 25, p95 25, max 25, utilization 100.0%"
+        );
+    }
+
+    #[test]
+    fn sampled_packings_are_feasible_and_reproducible() {
+        let instance = Instance {
+            capacity: 10,
+            sizes: vec![6, 5, 4, 3, 2, 12],
+        };
+        let packings = Sampler::new(instance.clone(), 3)
+            .take(50)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            packings,
+            Sampler::new(instance, 3).take(50).collect::<Vec<_>>()
+        );
+        for packing in &packings {
+            let mut items = packing.bins().concat();
+            items.sort_unstable();
+            assert_eq!(items, [0, 1, 2, 3, 4, 5]);
+            assert!(packing
+                .bins()
+                .iter()
+                .all(|bin| bin == &[5]
+                    || bin.iter().map(|&i| packing.sizes()[i]).sum::<usize>() <= 10));
+        }
+        let distinct = packings
+            .iter()
+            .map(Packing::fingerprint)
+            .collect::<std::collections::HashSet<_>>();
+        assert!(distinct.len() > 10);
+    }
+}
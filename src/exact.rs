@@ -0,0 +1,914 @@
+//! Exact bin packing.
+//!
+//! Exact strategies search for a packing into the fewest bins possible. The search takes
+//! exponential time in the worst case, so it can be capped, in which case it returns the best
+//! packing found so far along with a lower bound on the number of bins needed.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+use crate::offline::Limits;
+use crate::*;
+
+/// How many nodes to visit between checks of the deadline.
+const DEADLINE_INTERVAL: usize = 1024;
+
+/// An offline strategy that packs items into the fewest bins possible using a depth-first branch
+/// and bound search.
+///
+/// Items are placed largest first, trying every bin they fit into before opening a new one, and
+/// branches that cannot beat the best packing found so far are pruned. The search starts from a
+/// first fit decreasing packing, or a better one passed to [`BranchAndBound::solve_from`], so
+/// stopping it early still yields a reasonable packing.
+///
+/// As the search is depth first it only ever holds one partial packing, so its memory use is
+/// linear in the number of items no matter how long it runs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BranchAndBound {
+    /// The maximum number of search nodes to visit, or `None` to search until the packing is
+    /// proven optimal.
+    pub node_limit: Option<usize>,
+}
+
+/// The outcome of an exact search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    /// The index of the bin each item is packed into.
+    pub assignment: Vec<usize>,
+    /// The number of bins used.
+    pub bins: usize,
+    /// A lower bound on the number of bins needed, which equals `bins` if the packing is optimal.
+    pub lower_bound: usize,
+    /// The number of search nodes visited.
+    pub nodes: usize,
+}
+
+impl Solution {
+    /// Returns whether the packing is proven to use the fewest bins possible.
+    pub fn is_optimal(&self) -> bool {
+        self.bins == self.lower_bound
+    }
+}
+
+/// The state of a branch and bound search that stopped at its node limit, to resume it later,
+/// possibly in another process, see [`BranchAndBound::solve_resumable`].
+///
+/// Only branch and bound can be checkpointed. The metaheuristics, such as
+/// [`crate::metaheuristics::Grasp`] and [`crate::metaheuristics::AntColony`], draw from a random
+/// number generator whose state cannot be saved, so they have to be run again from their seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    capacity: usize,
+    sizes: Vec<usize>,
+    /// The best packing found so far.
+    assignment: Vec<usize>,
+    /// The bin chosen for each item placed on the way to the node the search stopped at.
+    path: Vec<usize>,
+    nodes: usize,
+}
+
+/// The bytes every serialized [`Checkpoint`] starts with, including a format version.
+const CHECKPOINT_MAGIC: &[u8] = b"bpck\x01";
+
+impl Checkpoint {
+    /// Returns the number of search nodes visited so far.
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Serializes the checkpoint into bytes that are the same on every platform.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CHECKPOINT_MAGIC.to_vec();
+        let mut write = |value: usize| bytes.extend((value as u64).to_le_bytes());
+        write(self.capacity);
+        write(self.sizes.len());
+        self.sizes.iter().for_each(|&size| write(size));
+        self.assignment.iter().for_each(|&bin| write(bin));
+        write(self.path.len());
+        self.path.iter().for_each(|&bin| write(bin));
+        write(self.nodes);
+        bytes
+    }
+
+    /// Reads a checkpoint serialized with [`Checkpoint::to_bytes`], returning `None` if the bytes
+    /// are not one.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut words = bytes.strip_prefix(CHECKPOINT_MAGIC)?.chunks(8);
+        let mut read = || {
+            let word = words.next()?.try_into().ok()?;
+            usize::try_from(u64::from_le_bytes(word)).ok()
+        };
+        let capacity = read()?;
+        let len = read()?;
+        let sizes = (0..len).map(|_| read()).collect::<Option<Vec<_>>>()?;
+        let assignment = (0..len).map(|_| read()).collect::<Option<Vec<_>>>()?;
+        let depth = read()?;
+        if depth > len {
+            return None;
+        }
+        let path = (0..depth).map(|_| read()).collect::<Option<Vec<_>>>()?;
+        let nodes = read()?;
+        if read().is_some() {
+            return None;
+        }
+        Some(Self {
+            capacity,
+            sizes,
+            assignment,
+            path,
+            nodes,
+        })
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bins, lower bound {}", self.bins, self.lower_bound)?;
+        if self.is_optimal() {
+            f.write_str(" (optimal)")?;
+        }
+        write!(f, ", {} nodes", self.nodes)
+    }
+}
+
+impl BranchAndBound {
+    /// Searches for a packing of the items into the fewest bins of type `B`.
+    pub fn solve<B: Bin>(&self, items: &[impl Item]) -> Solution {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        self.search(&Limits::of::<B>(&[]), &[], &sizes, None, None)
+    }
+
+    /// Searches for a packing of the items into the fewest bins of the given capacity, for callers
+    /// without a bin type.
+    pub fn solve_capacity(&self, capacity: usize, items: &[impl Item]) -> Solution {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        self.search(&Limits::capacity(capacity), &[], &sizes, None, None)
+    }
+
+    /// Searches for a packing of the items into the fewest bins of type `B`, starting from a known
+    /// packing that gives the index of the bin each item is packed into.
+    ///
+    /// The search only looks for packings that use fewer bins than the incumbent, so a good
+    /// incumbent prunes most of the search, and an optimal one is often proven so right away.
+    /// An incumbent that does not assign every item or overfills a bin is ignored.
+    pub fn solve_from<B: Bin>(&self, items: &[impl Item], incumbent: &[usize]) -> Solution {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        self.search(&Limits::of::<B>(&[]), &[], &sizes, Some(incumbent), None)
+    }
+
+    /// Searches for a packing of the items into the fewest bins of type `B`, keeping each item
+    /// that the corresponding entry of `pins` pins to a bin in that bin.
+    ///
+    /// Every bin up to the highest pin is used, and pinned items count towards the load of their
+    /// bin even if they overfill it.
+    pub fn solve_pinned<B: Bin>(&self, items: &[impl Item], pins: &[Option<usize>]) -> Solution {
+        let pin = |i| pins.get(i).copied().flatten();
+        let mut initial = vec![];
+        let mut free = vec![];
+        for (i, item) in items.iter().enumerate() {
+            match pin(i) {
+                Some(bin) => {
+                    if initial.len() <= bin {
+                        initial.resize(bin + 1, 0);
+                    }
+                    initial[bin] += item.size();
+                }
+                None => free.push(i),
+            }
+        }
+        let sizes = free.iter().map(|&i| items[i].size()).collect::<Vec<_>>();
+        let mut solution = self.search(&Limits::of::<B>(&[]), &initial, &sizes, None, None);
+        let mut assignment = solution.assignment.into_iter();
+        solution.assignment = (0..items.len())
+            .map(|i| pin(i).or_else(|| assignment.next()).unwrap_or_default())
+            .collect();
+        solution
+    }
+
+    /// Searches like [`BranchAndBound::improve`] until the search is done or the deadline has
+    /// passed.
+    pub fn improve_until(&self, packing: &Packing, deadline: Instant) -> Solution {
+        self.search(
+            &Limits::capacity(packing.capacity()),
+            &[],
+            packing.sizes(),
+            Some(&packing.assignment()),
+            Some(deadline),
+        )
+    }
+
+    /// Searches like [`BranchAndBound::solve_capacity`], and if the search stops at its node limit,
+    /// also returns a checkpoint to resume it from with [`BranchAndBound::resume`].
+    ///
+    /// Resumable searches do not take dominated bins out of the instance first.
+    pub fn solve_resumable(
+        &self,
+        capacity: usize,
+        items: &[impl Item],
+    ) -> (Solution, Option<Checkpoint>) {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        self.resumable(capacity, sizes, None)
+    }
+
+    /// Resumes a search from a checkpoint, visiting up to the node limit of nodes more, and returns
+    /// the best packing found along with a new checkpoint if it stops again.
+    ///
+    /// A search resumed until it is done finds the same packing and visits as many nodes as one
+    /// that never stopped.
+    pub fn resume(&self, checkpoint: &Checkpoint) -> (Solution, Option<Checkpoint>) {
+        self.resumable(
+            checkpoint.capacity,
+            checkpoint.sizes.clone(),
+            Some(checkpoint),
+        )
+    }
+
+    /// Searches like [`BranchAndBound::solve_resumable`], resuming from the checkpoint if given.
+    fn resumable(
+        &self,
+        capacity: usize,
+        sizes: Vec<usize>,
+        resume: Option<&Checkpoint>,
+    ) -> (Solution, Option<Checkpoint>) {
+        let limits = Limits::capacity(capacity);
+        let incumbent = resume.map(|checkpoint| &checkpoint.assignment[..]);
+        let (solution, path) = self.branch(&limits, &[], &sizes, incumbent, None, resume);
+        let checkpoint = path.map(|path| Checkpoint {
+            capacity,
+            sizes,
+            assignment: solution.assignment.clone(),
+            path,
+            nodes: solution.nodes,
+        });
+        (solution, checkpoint)
+    }
+
+    /// Searches for a packing of the same items into fewer bins than an existing packing, for
+    /// example to check whether a heuristic found an optimal packing.
+    pub fn improve(&self, packing: &Packing) -> Solution {
+        self.search(
+            &Limits::capacity(packing.capacity()),
+            &[],
+            packing.sizes(),
+            Some(&packing.assignment()),
+            None,
+        )
+    }
+
+    /// Searches for a packing of `sizes` that adds them to bins with the `initial` loads, opening
+    /// new bins as needed, until the search is done, the node limit is reached, or the deadline
+    /// has passed.
+    ///
+    /// Without initial bins, incumbent or limits on item sizes, bins fixed by
+    /// [`reduce::dominated_bins`] are taken out of the search.
+    fn search(
+        &self,
+        limits: &Limits,
+        initial: &[usize],
+        sizes: &[usize],
+        incumbent: Option<&[usize]>,
+        deadline: Option<Instant>,
+    ) -> Solution {
+        if !initial.is_empty() || incumbent.is_some() || !limits.unrestricted() {
+            return self
+                .branch(limits, initial, sizes, incumbent, deadline, None)
+                .0;
+        }
+        let reduction = reduce::dominated_bins(limits.capacity, sizes);
+        if reduction.bins.is_empty() {
+            return self.branch(limits, initial, sizes, None, deadline, None).0;
+        }
+        let rest = reduction.rest.iter().map(|&i| sizes[i]).collect::<Vec<_>>();
+        let (solution, _) = self.branch(limits, initial, &rest, None, deadline, None);
+        let fixed = reduction.bins.len();
+        let mut assignment = vec![0; sizes.len()];
+        for (bin, items) in reduction.bins.iter().enumerate() {
+            for &i in items {
+                assignment[i] = bin;
+            }
+        }
+        for (&i, bin) in reduction.rest.iter().zip(solution.assignment) {
+            assignment[i] = fixed + bin;
+        }
+        Solution {
+            assignment,
+            bins: fixed + solution.bins,
+            lower_bound: fixed + solution.lower_bound,
+            nodes: solution.nodes,
+        }
+    }
+
+    /// Searches like [`BranchAndBound::search`], without reducing the instance first, resuming
+    /// from the checkpoint if given. Also returns the path to the node the search stopped at, if
+    /// it did.
+    fn branch(
+        &self,
+        limits: &Limits,
+        initial: &[usize],
+        sizes: &[usize],
+        incumbent: Option<&[usize]>,
+        deadline: Option<Instant>,
+        resume: Option<&Checkpoint>,
+    ) -> (Solution, Option<Vec<usize>>) {
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+        let total = initial.iter().sum::<usize>() + sizes.iter().sum::<usize>();
+        let lower_bound = initial.len().max(total.div_ceil(limits.capacity.max(1)));
+
+        // Start from first fit decreasing.
+        let mut loads = initial.to_vec();
+        let mut assignment = vec![0; sizes.len()];
+        for &i in &order {
+            let bin = match (0..loads.len()).find(|&b| limits.fits(b, loads[b], sizes[i])) {
+                Some(bin) => bin,
+                None => {
+                    loads.push(0);
+                    loads.len() - 1
+                }
+            };
+            loads[bin] += sizes[i];
+            assignment[i] = bin;
+        }
+        if let Some((bins, incumbent)) =
+            incumbent.and_then(|incumbent| normalize(limits, initial, sizes, incumbent))
+        {
+            if bins < loads.len() {
+                loads.truncate(bins);
+                assignment = incumbent;
+            }
+        }
+
+        let nodes = resume.map_or(0, |checkpoint| checkpoint.nodes);
+        let mut search = Search {
+            limits,
+            sizes,
+            order: &order,
+            remaining: sizes.iter().sum(),
+            loads: initial.to_vec(),
+            current: vec![0; sizes.len()],
+            best: loads.len(),
+            assignment,
+            lower_bound,
+            nodes,
+            node_limit: nodes.saturating_add(self.node_limit.unwrap_or(usize::MAX)),
+            deadline,
+            stopped: false,
+            path: Vec::with_capacity(sizes.len()),
+            resume: resume.map(|checkpoint| checkpoint.path.clone()),
+        };
+        if search.best > lower_bound {
+            search.visit(0);
+        }
+        let bins = search.best;
+        let solution = Solution {
+            assignment: search.assignment,
+            bins,
+            // An exhausted search proves the incumbent optimal.
+            lower_bound: if search.stopped { lower_bound } else { bins },
+            nodes: search.nodes,
+        };
+        (solution, search.stopped.then_some(search.path))
+    }
+}
+
+impl offline::Strategy for BranchAndBound {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        self.pack(bins, items, None);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        self.pack(bins, items, Some(deadline))
+    }
+}
+
+impl BranchAndBound {
+    /// Packs items into the bins with the best packing found and returns whether it is optimal.
+    fn pack<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Option<Instant>,
+    ) -> bool {
+        let limits = Limits::of(bins);
+        let initial = bins.iter().map(Bin::load).collect::<Vec<_>>();
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let solution = self.search(&limits, &initial, &sizes, None, deadline);
+        offline::place(bins, items, &solution.assignment);
+        solution.is_optimal()
+    }
+}
+
+/// Returns up to `k` structurally distinct packings of the items into bins of the given capacity,
+/// those with the fewest bins first, and in a fixed order among packings with as many bins.
+///
+/// Two packings are distinct unless they only differ in the order of their bins or in which of
+/// several items of the same size goes where, so that callers can choose among packings that are
+/// really different by criteria of their own. Items larger than the capacity get a bin of their
+/// own. The number of packings grows exponentially with the number of items, so this suits small
+/// and medium instances.
+pub fn top_k(capacity: usize, items: &[impl Item], k: usize) -> Vec<Packing> {
+    let distinct = Distinct::new(capacity, items);
+    let mut packings = vec![];
+    if k == 0 {
+        return packings;
+    }
+    for bins in distinct.bin_counts() {
+        distinct.enumerate(bins, &mut |packing| {
+            packings.push(packing);
+            packings.len() < k
+        });
+        if packings.len() == k {
+            break;
+        }
+    }
+    packings
+}
+
+/// Returns every optimal packing of the items into bins of the given capacity, up to the order of
+/// the bins and swapping items of the same size, as distinguished by [`top_k`].
+///
+/// Like [`top_k`], this takes exponential time and suits instances of up to about 20 items.
+pub fn all_optimal(capacity: usize, items: &[impl Item]) -> Vec<Packing> {
+    let distinct = Distinct::new(capacity, items);
+    let mut packings = vec![];
+    for bins in distinct.bin_counts() {
+        distinct.enumerate(bins, &mut |packing| {
+            packings.push(packing);
+            true
+        });
+        if !packings.is_empty() {
+            break;
+        }
+    }
+    packings
+}
+
+/// Enumerates packings up to symmetry, as described at [`top_k`].
+struct Distinct {
+    capacity: usize,
+    /// The sizes of all items.
+    sizes: Vec<usize>,
+    /// The distinct sizes of the items that fit into a bin, largest first.
+    kinds: Vec<usize>,
+    /// The indices of the items of each distinct size, in order.
+    items: Vec<Vec<usize>>,
+    /// The indices of the items larger than the capacity.
+    oversized: Vec<usize>,
+}
+
+impl Distinct {
+    fn new(capacity: usize, items: &[impl Item]) -> Self {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+        let (mut oversized, fitting) = order
+            .into_iter()
+            .partition::<Vec<_>, _>(|&i| sizes[i] > capacity);
+        let mut kinds = vec![];
+        let mut grouped = Vec::<Vec<usize>>::new();
+        for i in fitting {
+            if kinds.last() != Some(&sizes[i]) {
+                kinds.push(sizes[i]);
+                grouped.push(vec![]);
+            }
+            grouped.last_mut().unwrap().push(i);
+        }
+        oversized.sort_unstable();
+        Self {
+            capacity,
+            sizes,
+            kinds,
+            items: grouped,
+            oversized,
+        }
+    }
+
+    /// Returns the numbers of bins the items that fit may be packed into, fewest first.
+    fn bin_counts(&self) -> std::ops::RangeInclusive<usize> {
+        let fitting = self
+            .items
+            .iter()
+            .flatten()
+            .map(|&i| self.sizes[i])
+            .collect::<Vec<_>>();
+        bounds::l2(self.capacity, &fitting)..=fitting.len()
+    }
+
+    /// Calls `found` with every distinct packing that puts the items that fit into exactly `bins`
+    /// bins, until it returns `false`, and returns whether it never did.
+    fn enumerate(&self, bins: usize, found: &mut impl FnMut(Packing) -> bool) -> bool {
+        let mut remaining = self.items.iter().map(Vec::len).collect::<Vec<_>>();
+        self.fill(&mut remaining, bins, &mut vec![], found)
+    }
+
+    /// Packs the remaining items of each size into exactly `left` more bins, each holding at most
+    /// as much of each size as the previous one in lexicographic order, so that every packing is
+    /// only found once.
+    fn fill(
+        &self,
+        remaining: &mut Vec<usize>,
+        left: usize,
+        bins: &mut Vec<Vec<usize>>,
+        found: &mut impl FnMut(Packing) -> bool,
+    ) -> bool {
+        let Some(largest) = remaining.iter().position(|&count| count > 0) else {
+            return left > 0 || found(self.packing(bins));
+        };
+        let total = (0..self.kinds.len())
+            .map(|t| remaining[t] * self.kinds[t])
+            .sum::<usize>();
+        if left == 0 || total.div_ceil(self.capacity.max(1)) > left {
+            return true;
+        }
+        let mut bin = vec![0; self.kinds.len()];
+        // A previous bin with larger items than remain is already lexicographically larger.
+        let previous = bins
+            .last()
+            .filter(|previous| previous[..largest].iter().all(|&count| count == 0))
+            .cloned();
+        self.choose(
+            largest,
+            self.capacity,
+            previous.as_deref(),
+            &mut bin,
+            remaining,
+            left,
+            bins,
+            found,
+        )
+    }
+
+    /// Chooses how many items of each size from `kind` on go into the next bin, most first.
+    ///
+    /// The bin has to hold some of the largest remaining size, as the items of that size would
+    /// not fit into the later bins otherwise.
+    #[allow(clippy::too_many_arguments)]
+    fn choose(
+        &self,
+        kind: usize,
+        room: usize,
+        bound: Option<&[usize]>,
+        bin: &mut Vec<usize>,
+        remaining: &mut Vec<usize>,
+        left: usize,
+        bins: &mut Vec<Vec<usize>>,
+        found: &mut impl FnMut(Packing) -> bool,
+    ) -> bool {
+        if kind == self.kinds.len() {
+            bins.push(bin.clone());
+            let more = self.fill(remaining, left - 1, bins, found);
+            bins.pop();
+            return more;
+        }
+        let size = self.kinds[kind];
+        let mut most = remaining[kind].min(room.checked_div(size).unwrap_or(usize::MAX));
+        if let Some(bound) = bound {
+            most = most.min(bound[kind]);
+        }
+        let least = usize::from(bin.iter().all(|&count| count == 0));
+        for count in (least..=most).rev() {
+            bin[kind] = count;
+            remaining[kind] -= count;
+            let bound = bound.filter(|bound| count == bound[kind]);
+            let more = self.choose(
+                kind + 1,
+                room - count * size,
+                bound,
+                bin,
+                remaining,
+                left,
+                bins,
+                found,
+            );
+            remaining[kind] += count;
+            if !more {
+                bin[kind] = 0;
+                return false;
+            }
+        }
+        bin[kind] = 0;
+        true
+    }
+
+    /// Turns the counts of each size in each bin into a packing of the items.
+    fn packing(&self, bins: &[Vec<usize>]) -> Packing {
+        let mut next = vec![0; self.kinds.len()];
+        let mut packed = self.oversized.iter().map(|&i| vec![i]).collect::<Vec<_>>();
+        for counts in bins {
+            let mut bin = vec![];
+            for (kind, &count) in counts.iter().enumerate() {
+                bin.extend_from_slice(&self.items[kind][next[kind]..next[kind] + count]);
+                next[kind] += count;
+            }
+            packed.push(bin);
+        }
+        Packing::relabeled(self.capacity, self.sizes.clone(), packed)
+    }
+}
+
+/// Renumbers the bins of an incumbent assignment so that new bins follow the `initial` ones in
+/// order of first use, returning the number of bins and the renumbered assignment, or `None` if
+/// the incumbent is not a valid packing.
+fn normalize(
+    limits: &Limits,
+    initial: &[usize],
+    sizes: &[usize],
+    incumbent: &[usize],
+) -> Option<(usize, Vec<usize>)> {
+    if incumbent.len() != sizes.len() {
+        return None;
+    }
+    let mut loads = initial.to_vec();
+    let mut renumbered = HashMap::new();
+    let mut assignment = Vec::with_capacity(sizes.len());
+    for (&size, &bin) in sizes.iter().zip(incumbent) {
+        let bin = if bin < initial.len() {
+            bin
+        } else {
+            *renumbered.entry(bin).or_insert_with(|| {
+                loads.push(0);
+                loads.len() - 1
+            })
+        };
+        if !limits.fits(bin, loads[bin], size) {
+            return None;
+        }
+        loads[bin] += size;
+        assignment.push(bin);
+    }
+    Some((loads.len(), assignment))
+}
+
+/// The state of a branch and bound search.
+struct Search<'a> {
+    limits: &'a Limits,
+    sizes: &'a [usize],
+    order: &'a [usize],
+    /// The total size of the items not yet placed.
+    remaining: usize,
+    loads: Vec<usize>,
+    current: Vec<usize>,
+    best: usize,
+    assignment: Vec<usize>,
+    lower_bound: usize,
+    nodes: usize,
+    node_limit: usize,
+    deadline: Option<Instant>,
+    stopped: bool,
+    /// The bin chosen for each item placed so far, which is kept as it is once stopped.
+    path: Vec<usize>,
+    /// The path to the node a resumed search continues at, and up to which it does not count
+    /// nodes again, shortened as the search moves on.
+    resume: Option<Vec<usize>>,
+}
+
+impl Search<'_> {
+    /// Places the item at `depth` in the order and everything after it, returning once the
+    /// incumbent is known to be optimal or the search has been stopped.
+    fn visit(&mut self, depth: usize) {
+        if depth == self.order.len() {
+            self.best = self.loads.len();
+            self.assignment.clone_from(&self.current);
+            return;
+        }
+        let resume = self.resume.as_ref();
+        let replaying = resume.is_some_and(|path| depth <= path.len());
+        let start = resume
+            .and_then(|path| path.get(depth))
+            .copied()
+            .unwrap_or(0);
+        if !replaying {
+            self.nodes += 1;
+            if self.nodes >= self.node_limit
+                || (self.nodes.is_multiple_of(DEADLINE_INTERVAL)
+                    && self
+                        .deadline
+                        .is_some_and(|deadline| Instant::now() >= deadline))
+            {
+                self.stopped = true;
+                return;
+            }
+        }
+
+        let item = self.order[depth];
+        let size = self.sizes[item];
+        self.remaining -= size;
+        for bin in start..=self.loads.len() {
+            if bin > start {
+                if let Some(path) = &mut self.resume {
+                    path.truncate(depth);
+                }
+            }
+            let opened = bin == self.loads.len();
+            if opened {
+                if self.loads.len() + 1 >= self.best || !self.limits.fits(bin, 0, size) {
+                    break;
+                }
+                self.loads.push(0);
+            } else if !self.limits.fits(bin, self.loads[bin], size)
+                // Bins with equal loads and limits are interchangeable, so only try the first.
+                || (0..bin).any(|other| {
+                    self.loads[other] == self.loads[bin]
+                        && self.limits.max_item_size(other) == self.limits.max_item_size(bin)
+                })
+            {
+                continue;
+            }
+
+            self.loads[bin] += size;
+            let total = self.loads.iter().sum::<usize>() + self.remaining;
+            if self
+                .loads
+                .len()
+                .max(total.div_ceil(self.limits.capacity.max(1)))
+                < self.best
+            {
+                self.current[item] = bin;
+                self.path.push(bin);
+                self.visit(depth + 1);
+                if self.stopped {
+                    break;
+                }
+                self.path.pop();
+            }
+            self.loads[bin] -= size;
+            if opened {
+                self.loads.pop();
+            }
+            if self.stopped || self.best == self.lower_bound {
+                break;
+            }
+        }
+        self.remaining += size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn finds_optimal_packing() {
+        // First fit decreasing needs three bins here, but two suffice.
+        let sizes = [4, 4, 3, 3, 3, 3];
+        let solution = BranchAndBound::default().solve::<BinImpl>(&sizes);
+        assert_eq!(solution.bins, 2);
+        assert!(solution.is_optimal());
+        let mut loads = [0; 2];
+        for (&size, &bin) in sizes.iter().zip(&solution.assignment) {
+            loads[bin] += size;
+        }
+        assert_eq!(loads, [10, 10]);
+    }
+
+    #[test]
+    fn pinned_items_stay_in_their_bins() {
+        let sizes = [4, 4, 3, 3, 3, 3];
+        let pins = [Some(0), Some(0), None, None, None, None];
+        let solution = BranchAndBound::default().solve_pinned::<BinImpl>(&sizes, &pins);
+        assert_eq!(solution.bins, 3);
+        assert!(solution.is_optimal());
+        assert_eq!(solution.assignment[..2], [0, 0]);
+        let mut loads = [0; 3];
+        for (&size, &bin) in sizes.iter().zip(&solution.assignment) {
+            loads[bin] += size;
+        }
+        assert!(loads.iter().all(|&load| load <= 10));
+    }
+
+    #[test]
+    fn node_limit_returns_incumbent_and_bound() {
+        // No bins are dominated here, so the search has to find the packing.
+        let sizes = [5, 4, 3, 3, 3, 2];
+        let solution = BranchAndBound {
+            node_limit: Some(1),
+        }
+        .solve::<BinImpl>(&sizes);
+        assert_eq!(solution.bins, 3);
+        assert_eq!(solution.lower_bound, 2);
+        assert!(!solution.is_optimal());
+
+        let mut bins: Vec<BinImpl> = vec![];
+        let mut items = sizes.to_vec();
+        offline::Strategy::pack_all(&BranchAndBound::default(), &mut bins, &mut items);
+        assert_eq!(bins.len(), 2);
+    }
+
+    #[test]
+    fn dominated_bins_are_left_out_of_the_search() {
+        let solution = BranchAndBound {
+            node_limit: Some(1),
+        }
+        .solve::<BinImpl>(&[4, 4, 3, 3, 3, 3]);
+        assert_eq!(solution.bins, 2);
+        assert!(solution.is_optimal());
+        assert_eq!(solution.assignment, [0, 1, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn warm_start_proves_incumbent_optimal() {
+        let sizes = [4, 4, 3, 3, 3, 3];
+        let incumbent = [5, 7, 5, 5, 7, 7];
+        let solution = BranchAndBound {
+            node_limit: Some(1),
+        }
+        .solve_from::<BinImpl>(&sizes, &incumbent);
+        assert_eq!(solution.bins, 2);
+        assert!(solution.is_optimal());
+        assert_eq!(solution.assignment, [0, 1, 0, 0, 1, 1]);
+        assert_eq!(solution.nodes, 0);
+
+        let packing = Algorithm::FirstFitDecreasing.pack(10, &sizes);
+        assert_eq!(packing.len(), 3);
+        let solution = BranchAndBound::default().improve(&packing);
+        assert_eq!(solution.bins, 2);
+
+        // Overfull incumbents are ignored.
+        let solution = BranchAndBound::default().solve_from::<BinImpl>(&sizes, &[0; 6]);
+        assert_eq!(solution.bins, 2);
+    }
+
+    #[test]
+    fn resumed_search_matches_uninterrupted_one() {
+        let sizes = [5, 4, 3, 3, 3, 2];
+        let (full, checkpoint) = BranchAndBound::default().solve_resumable(10, &sizes);
+        assert!(checkpoint.is_none());
+        assert!(full.is_optimal());
+
+        let search = BranchAndBound {
+            node_limit: Some(1),
+        };
+        let (mut solution, mut checkpoint) = search.solve_resumable(10, &sizes);
+        let mut stops = 0;
+        while let Some(stopped) = checkpoint {
+            let bytes = stopped.to_bytes();
+            let restored = Checkpoint::from_bytes(&bytes).unwrap();
+            assert_eq!(restored, stopped);
+            (solution, checkpoint) = search.resume(&restored);
+            stops += 1;
+        }
+        assert!(stops > 1);
+        assert_eq!(solution, full);
+
+        assert_eq!(Checkpoint::from_bytes(b"bpck"), None);
+        let (_, stopped) = search.solve_resumable(10, &sizes);
+        let bytes = stopped.unwrap().to_bytes();
+        assert_eq!(Checkpoint::from_bytes(&bytes[..bytes.len() - 1]), None);
+    }
+
+    #[test]
+    fn top_k_finds_distinct_packings() {
+        let packings = top_k(10, &[6, 4, 5, 5], 3);
+        let bins = packings.iter().map(Packing::bins).collect::<Vec<_>>();
+        assert_eq!(
+            bins,
+            [
+                &[vec![0, 1], vec![2, 3]][..],
+                &[vec![0, 1], vec![2], vec![3]],
+                &[vec![0], vec![2, 3], vec![1]]
+            ]
+        );
+        assert_eq!(top_k(10, &[6, 4, 5, 5], 0), Vec::<Packing>::new());
+        assert_eq!(top_k(10, &[12, 5], 5).len(), 1);
+    }
+
+    #[test]
+    fn all_optimal_packings_are_enumerated() {
+        let packings = all_optimal(10, &[5, 5, 3, 3, 2, 2]);
+        let bins = packings.iter().map(Packing::bins).collect::<Vec<_>>();
+        assert_eq!(
+            bins,
+            [
+                &[vec![0, 1], vec![2, 3, 4, 5]][..],
+                &[vec![0, 2, 4], vec![1, 3, 5]]
+            ]
+        );
+        assert_eq!(all_optimal(10, &[4, 4, 3, 3, 3, 3]).len(), 1);
+        let empty = all_optimal(10, &[0; 0]);
+        assert_eq!(empty.len(), 1);
+        assert!(empty[0].is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! External-memory packing for instances too large to hold in memory.
+//!
+//! Item sizes are sorted on disk, in runs that fit in memory which are then merged, and packed
+//! first fit decreasing straight from the merged runs. Only the loads of the bins stay in memory,
+//! so instances of hundreds of millions of items can be packed as long as their bins fit.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes the run files of sorts in the same process.
+static SORTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Item sizes sorted on disk in decreasing order, as a set of sorted runs that are merged while
+/// reading. The run files are removed when this is dropped.
+pub struct Runs {
+    paths: Vec<PathBuf>,
+}
+
+impl Runs {
+    /// Sorts item sizes into runs of at most `run_len` items each, written to files in `dir`.
+    pub fn sort(
+        sizes: impl IntoIterator<Item = usize>,
+        run_len: usize,
+        dir: &Path,
+    ) -> io::Result<Self> {
+        let sort = SORTS.fetch_add(1, Ordering::Relaxed);
+        let mut runs = Self { paths: vec![] };
+        let mut run = Vec::with_capacity(run_len.max(1));
+        for (index, size) in sizes.into_iter().enumerate() {
+            run.push((size, index));
+            if run.len() == run_len.max(1) {
+                runs.write(&mut run, dir, sort)?;
+            }
+        }
+        if !run.is_empty() {
+            runs.write(&mut run, dir, sort)?;
+        }
+        Ok(runs)
+    }
+
+    /// Sorts a run largest first, ties in item order, and writes it to a new file.
+    fn write(&mut self, run: &mut Vec<(usize, usize)>, dir: &Path, sort: usize) -> io::Result<()> {
+        run.sort_unstable_by_key(|&(size, index)| (Reverse(size), index));
+        let path = dir.join(format!(
+            "bin-packing-{}-{sort}-{}.run",
+            std::process::id(),
+            self.paths.len()
+        ));
+        self.paths.push(path.clone());
+        let mut file = BufWriter::new(File::create(path)?);
+        for (size, index) in run.drain(..) {
+            file.write_all(&(size as u64).to_le_bytes())?;
+            file.write_all(&(index as u64).to_le_bytes())?;
+        }
+        file.flush()
+    }
+
+    /// Returns the sizes of all items largest first as pairs of item index and size, ties in
+    /// item order.
+    pub fn merge(&self) -> io::Result<Merge> {
+        let mut merge = Merge {
+            files: vec![],
+            heap: BinaryHeap::new(),
+        };
+        for path in &self.paths {
+            merge.files.push(BufReader::new(File::open(path)?));
+            merge.refill(merge.files.len() - 1)?;
+        }
+        Ok(merge)
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// An iterator over the merged runs of a [`Runs`], created with [`Runs::merge`].
+pub struct Merge {
+    files: Vec<BufReader<File>>,
+    /// The next item of each run that is not exhausted, by size, item index and run.
+    heap: BinaryHeap<(usize, Reverse<usize>, usize)>,
+}
+
+impl Merge {
+    /// Reads the next item of a run onto the heap, if there is one.
+    fn refill(&mut self, run: usize) -> io::Result<()> {
+        let mut record = [0; 16];
+        match self.files[run].read_exact(&mut record) {
+            Ok(()) => {
+                let (size, index) = record.split_at(8);
+                let size = u64::from_le_bytes(size.try_into().unwrap()) as usize;
+                let index = u64::from_le_bytes(index.try_into().unwrap()) as usize;
+                self.heap.push((size, Reverse(index), run));
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<(usize, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (size, Reverse(index), run) = self.heap.pop()?;
+        Some(self.refill(run).map(|()| (index, size)))
+    }
+}
+
+/// Packs item sizes into bins of the given capacity first fit decreasing, sorting them on disk in
+/// runs of at most `run_len` items in `dir`, and returns the loads of the bins.
+///
+/// Rather than collecting the assignment, `place` is called with the index of each item and the
+/// bin it is packed into, largest items first. Items larger than the capacity get a bin of their
+/// own.
+pub fn first_fit_decreasing(
+    capacity: usize,
+    sizes: impl IntoIterator<Item = usize>,
+    run_len: usize,
+    dir: &Path,
+    mut place: impl FnMut(usize, usize),
+) -> io::Result<Vec<usize>> {
+    let runs = Runs::sort(sizes, run_len, dir)?;
+    let mut bins = FirstFitTree::new(capacity);
+    for item in runs.merge()? {
+        let (index, size) = item?;
+        place(index, bins.pack(size));
+    }
+    Ok(bins.loads)
+}
+
+/// Bin loads with a tree of the most available capacity in each range of bins, to find the first
+/// bin an item fits into in logarithmic time.
+struct FirstFitTree {
+    capacity: usize,
+    loads: Vec<usize>,
+    /// The available capacity of each bin at the leaves, starting at index `leaves`, with empty
+    /// bins past the open ones, and the maximum of its children at each inner node.
+    tree: Vec<usize>,
+    leaves: usize,
+}
+
+impl FirstFitTree {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            loads: vec![],
+            tree: vec![capacity; 2],
+            leaves: 1,
+        }
+    }
+
+    /// Packs an item into the first bin it fits into, opening a new bin if needed, and returns
+    /// the index of the bin.
+    fn pack(&mut self, size: usize) -> usize {
+        let bin = match self.first(size) {
+            Some(bin) => bin,
+            None => self.loads.len(),
+        };
+        if bin == self.loads.len() {
+            if bin == self.leaves {
+                self.grow();
+            }
+            self.loads.push(0);
+        }
+        self.loads[bin] += size;
+        let mut node = bin + self.leaves;
+        self.tree[node] = self.capacity.saturating_sub(self.loads[bin]);
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node].max(self.tree[2 * node + 1]);
+        }
+        bin
+    }
+
+    /// Returns the first bin with at least `size` available, which may be the first empty bin.
+    fn first(&self, size: usize) -> Option<usize> {
+        if self.tree[1] < size {
+            return None;
+        }
+        let mut node = 1;
+        while node < self.leaves {
+            node = if self.tree[2 * node] >= size {
+                2 * node
+            } else {
+                2 * node + 1
+            };
+        }
+        Some(node - self.leaves)
+    }
+
+    /// Doubles the number of leaves.
+    fn grow(&mut self) {
+        self.leaves *= 2;
+        self.tree = vec![self.capacity; 2 * self.leaves];
+        for (bin, load) in self.loads.iter().enumerate() {
+            self.tree[self.leaves + bin] = self.capacity.saturating_sub(*load);
+        }
+        for node in (1..self.leaves).rev() {
+            self.tree[node] = self.tree[2 * node].max(self.tree[2 * node + 1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_from_sorted_runs() {
+        let sizes = [2, 3, 8, 7, 5, 5, 12, 4];
+        let mut assignment = vec![0; sizes.len()];
+        let loads = first_fit_decreasing(10, sizes, 3, &std::env::temp_dir(), |item, bin| {
+            assignment[item] = bin
+        })
+        .unwrap();
+        assert_eq!(loads, vec![12, 10, 10, 10, 4]);
+        assert_eq!(assignment, vec![1, 2, 1, 2, 3, 3, 0, 4]);
+    }
+}
@@ -0,0 +1,99 @@
+//! A C API, enabled by the `ffi` feature.
+//!
+//! Build the shared library with `cargo rustc --lib --release --features ffi --crate-type cdylib`,
+//! or use `staticlib` for a static one.
+//!
+//! The matching header is `include/bin_packing.h`, which is generated with
+//! `cbindgen --config cbindgen.toml --output include/bin_packing.h`.
+
+use std::ffi::{c_char, CStr};
+
+use crate::Algorithm;
+
+/// A null pointer was passed.
+pub const BIN_PACKING_ERROR_NULL: i64 = -1;
+/// The strategy name is not valid UTF-8 or does not name a known strategy.
+pub const BIN_PACKING_ERROR_STRATEGY: i64 = -2;
+/// An item is larger than the bin capacity.
+pub const BIN_PACKING_ERROR_TOO_LARGE: i64 = -3;
+
+/// Packs `len` items with the sizes in `sizes` into bins of the given capacity, using the
+/// strategy named by the null-terminated string `strategy`, such as `"ffd"`.
+///
+/// Writes the index of the bin each item is packed into to the corresponding element of
+/// `assignment`, and returns the number of bins used, or one of the negative
+/// `BIN_PACKING_ERROR_*` codes, in which case `assignment` is left untouched.
+///
+/// # Safety
+///
+/// `sizes` and `assignment` must each point to `len` valid `u64`s, which may only be null if `len`
+/// is zero, and `strategy` must point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn bin_packing_pack(
+    sizes: *const u64,
+    len: usize,
+    capacity: u64,
+    strategy: *const c_char,
+    assignment: *mut u64,
+) -> i64 {
+    if strategy.is_null() || (len > 0 && (sizes.is_null() || assignment.is_null())) {
+        return BIN_PACKING_ERROR_NULL;
+    }
+    let Some(algorithm) = CStr::from_ptr(strategy)
+        .to_str()
+        .ok()
+        .and_then(|name| name.parse::<Algorithm>().ok())
+    else {
+        return BIN_PACKING_ERROR_STRATEGY;
+    };
+    let sizes = if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(sizes, len)
+    };
+    if sizes.iter().any(|&size| size > capacity) {
+        return BIN_PACKING_ERROR_TOO_LARGE;
+    }
+    let sizes = sizes.iter().map(|&size| size as usize).collect::<Vec<_>>();
+    let packing = algorithm.pack(capacity as usize, &sizes);
+    if len > 0 {
+        let assignment = std::slice::from_raw_parts_mut(assignment, len);
+        for (out, bin) in assignment.iter_mut().zip(packing.assignment()) {
+            *out = bin as u64;
+        }
+    }
+    packing.len() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_through_c_api() {
+        let sizes = [6u64, 4, 7];
+        let mut assignment = [0u64; 3];
+        let bins = unsafe {
+            bin_packing_pack(
+                sizes.as_ptr(),
+                sizes.len(),
+                10,
+                c"nf".as_ptr(),
+                assignment.as_mut_ptr(),
+            )
+        };
+        assert_eq!(bins, 2);
+        assert_eq!(assignment, [0, 0, 1]);
+
+        let bins = unsafe {
+            bin_packing_pack(
+                sizes.as_ptr(),
+                sizes.len(),
+                10,
+                c"bogus".as_ptr(),
+                assignment.as_mut_ptr(),
+            )
+        };
+        assert_eq!(bins, BIN_PACKING_ERROR_STRATEGY);
+    }
+}
@@ -0,0 +1,205 @@
+//! Packing in two levels: items into boxes, and boxes into containers such as pallets.
+//!
+//! [`pack`] coordinates both levels. Alternatively, bins packed on their own can be packed as
+//! items into larger bins with [`Nested`] or [`footprints`], and [`flatten`] turns the result
+//! back into a packing of the original items.
+
+use std::cmp::Reverse;
+
+use crate::{Bin, Item, Packing};
+
+/// How much space a bin takes when packed as an item into a larger bin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Footprint {
+    /// The bin takes up its whole capacity, like a box of a fixed size.
+    #[default]
+    Capacity,
+    /// The bin takes up only its load, like a bag or a file that is as large as its contents.
+    Load,
+}
+
+/// A bin packed as an item into a larger bin, taking up the space given by its footprint.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Nested<B> {
+    /// The bin.
+    pub bin: B,
+    /// How much space the bin takes.
+    pub footprint: Footprint,
+}
+
+impl<B: Bin> Item for Nested<B> {
+    fn size(&self) -> usize {
+        match self.footprint {
+            Footprint::Capacity => B::capacity(),
+            Footprint::Load => self.bin.load(),
+        }
+    }
+}
+
+/// Returns the size of each bin of a packing when packed as an item into a larger bin, in the
+/// order of the bins.
+pub fn footprints(inner: &Packing, footprint: Footprint) -> Vec<usize> {
+    match footprint {
+        Footprint::Capacity => vec![inner.capacity(); inner.len()],
+        Footprint::Load => inner.loads(),
+    }
+}
+
+/// Returns the packing of the original items into the outer bins, given a packing of the items
+/// into inner bins and a packing of the inner bins, numbered like their [`footprints`], into outer
+/// bins. This gives the statistics of the outer bins in terms of the items they hold.
+///
+/// Returns `None` unless the outer packing packs exactly one item per inner bin.
+pub fn flatten(inner: &Packing, outer: &Packing) -> Option<Packing> {
+    if outer.sizes().len() != inner.len() {
+        return None;
+    }
+    let bins = outer
+        .bins()
+        .iter()
+        .map(|bins| bins.iter().flat_map(|&b| inner.bins()[b].clone()).collect())
+        .collect();
+    Some(Packing::relabeled(
+        outer.capacity(),
+        inner.sizes().to_vec(),
+        bins,
+    ))
+}
+
+/// The capacities of the two levels of [`pack`], in the units of the item sizes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TwoLevel {
+    /// The most a box can hold.
+    pub box_capacity: usize,
+    /// The most a container can hold, counting each box as its load plus the overhead.
+    pub container_capacity: usize,
+    /// The space each box takes in a container on top of its load, such as packaging.
+    pub overhead: usize,
+}
+
+/// Items packed into boxes and boxes into containers by [`pack`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Shipment {
+    /// The indices of the items in each box of each container.
+    pub containers: Vec<Vec<Vec<usize>>>,
+}
+
+impl Shipment {
+    /// Returns the number of boxes in all containers.
+    pub fn boxes(&self) -> usize {
+        self.containers.iter().map(Vec::len).sum()
+    }
+}
+
+/// Packs items into boxes that are only as large as their load, and the boxes into containers,
+/// filling one container at a time.
+///
+/// Packing each level on its own fills boxes as far as possible first, and then the full boxes
+/// may leave room in containers that no other box fits into. Instead, each box here holds at most
+/// what is left of its container, so the last box of a container uses up its room. Each box is
+/// filled with the largest remaining items that fit, like [`crate::offline::FirstFitDecreasing`]
+/// filling a single bin. Items that fit into no box get a box and a container of their own.
+pub fn pack(levels: &TwoLevel, items: &[impl Item]) -> Shipment {
+    let mut remaining = (0..items.len()).collect::<Vec<_>>();
+    remaining.sort_by_key(|&i| Reverse(items[i].size()));
+    let mut containers = vec![];
+    while !remaining.is_empty() {
+        let mut container = vec![];
+        let mut room = levels.container_capacity;
+        while let Some(capacity) = room.checked_sub(levels.overhead) {
+            let capacity = capacity.min(levels.box_capacity);
+            let mut load = 0;
+            let mut packed = vec![];
+            remaining.retain(|&i| {
+                let fits = load + items[i].size() <= capacity;
+                if fits {
+                    load += items[i].size();
+                    packed.push(i);
+                }
+                !fits
+            });
+            if packed.is_empty() {
+                break;
+            }
+            container.push(packed);
+            room -= load + levels.overhead;
+        }
+        if container.is_empty() {
+            container.push(vec![remaining.remove(0)]);
+        }
+        containers.push(container);
+    }
+    Shipment { containers }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+
+    #[test]
+    fn boxes_use_up_the_room_of_containers() {
+        let levels = TwoLevel {
+            box_capacity: 10,
+            container_capacity: 25,
+            overhead: 0,
+        };
+        // Full boxes of 10 would only fit two to a container, needing three containers.
+        let shipment = pack(&levels, &[5; 10]);
+        assert_eq!(
+            shipment.containers,
+            [
+                vec![vec![0, 1], vec![2, 3], vec![4]],
+                vec![vec![5, 6], vec![7, 8], vec![9]]
+            ]
+        );
+        assert_eq!(shipment.boxes(), 6);
+
+        let levels = TwoLevel {
+            overhead: 2,
+            ..levels
+        };
+        let shipment = pack(&levels, &[12, 8, 7, 3]);
+        assert_eq!(
+            shipment.containers,
+            [vec![vec![1], vec![2, 3]], vec![vec![0]]]
+        );
+    }
+
+    #[test]
+    fn nested_bins_flatten_into_their_items() {
+        let boxes = Algorithm::FirstFitDecreasing.pack(10, &[5, 4, 5, 3]);
+        assert_eq!(boxes.bins(), &[vec![0, 2], vec![1, 3]]);
+        assert_eq!(footprints(&boxes, Footprint::Capacity), [10, 10]);
+        let sizes = footprints(&boxes, Footprint::Load);
+        assert_eq!(sizes, [10, 7]);
+
+        let pallets = Algorithm::FirstFitDecreasing.pack(20, &sizes);
+        let flat = flatten(&boxes, &pallets).unwrap();
+        assert_eq!(flat.bins(), &[vec![0, 2, 1, 3]]);
+        assert_eq!(flat.loads(), [17]);
+        assert_eq!(flatten(&boxes, &boxes), None);
+
+        #[derive(Default)]
+        struct BinImpl {
+            used: usize,
+        }
+        impl Bin for BinImpl {
+            fn capacity() -> usize {
+                10
+            }
+            fn available(&self) -> usize {
+                Self::capacity() - self.used
+            }
+            fn pack(&mut self, item: impl Item) {
+                self.used += item.size();
+            }
+        }
+        let full = |footprint| Nested {
+            bin: BinImpl { used: 7 },
+            footprint,
+        };
+        assert_eq!(full(Footprint::Capacity).size(), 10);
+        assert_eq!(full(Footprint::Load).size(), 7);
+    }
+}
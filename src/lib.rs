@@ -2,9 +2,55 @@
 //!
 //! Strategies are dividided into two categories: online and offline. Online strategies pack items
 //! into bins as they arrive, while offline strategies have access to all items in advance.
+//!
+//! Randomized strategies and tools never use a global random number generator. They take a
+//! generator or a seed from the caller instead, so a whole run can be reproduced from one seed.
+//! The [`rand`] crate is re-exported to make compatible generators easy to come by.
+//!
+//! Packing is deterministic: strategies run on the calling thread, sort stably and break ties by
+//! bin index, so the same items, options and seed produce the same packing on every machine.
+//! Nothing in the crate packs in parallel, so results cannot depend on the number of threads.
 
+pub mod adversarial;
+pub mod arena;
+pub mod bench_quality;
+pub mod bounds;
+pub mod certificate;
+pub mod constraints;
+pub mod covering;
+pub mod dot;
+pub mod eval;
+pub mod exact;
+#[cfg(feature = "external")]
+pub mod external;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod hierarchy;
+pub mod memo;
+pub mod metaheuristics;
 pub mod offline;
 pub mod online;
+pub mod packer;
+mod packing;
+pub mod pipeline;
+pub mod planning;
+pub mod pools;
+#[cfg(feature = "python")]
+mod python;
+pub mod reduce;
+pub mod result;
+pub mod sizes;
+pub mod solve;
+pub mod units;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use rand;
+
+pub use packing::{
+    Algorithm, Consolidation, Move, PackError, Packing, PackingBuilder, Progress, UnknownAlgorithm,
+};
+pub use result::{PackingResult, Stats};
 
 /// A bin that can hold items.
 pub trait Bin: Default {
@@ -14,10 +60,95 @@ pub trait Bin: Default {
     fn available(&self) -> usize;
     /// Packs an item into the bin.
     fn pack(&mut self, item: impl Item);
+
+    /// Returns how much of the capacity of the bin is taken up, which may exceed the capacity if
+    /// the bin has a [`Bin::tolerance`].
+    ///
+    /// The default derives this from [`Bin::available`], so bins that can be overfull need to
+    /// override it.
+    fn load(&self) -> usize {
+        Self::capacity() - self.available()
+    }
+
+    /// Returns by how much the items in a bin may exceed its capacity, which is zero unless
+    /// overridden.
+    ///
+    /// A tolerance suits items whose sizes are only estimates. Strategies then fill bins up to
+    /// their capacity plus the tolerance, so bins using it should report no available capacity
+    /// once overfull and the actual [`Bin::load`].
+    fn tolerance() -> usize {
+        0
+    }
+
+    /// Returns the size of the largest item the bin accepts regardless of its available
+    /// capacity, which is unlimited unless overridden.
+    ///
+    /// This suits bins that stand for machines with a limit per task, for example. New bins get
+    /// the limit of [`Default::default`].
+    fn max_item_size(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Returns the name of the group the bin belongs to, such as a pool of machines with the same
+    /// kind of storage, or `None` if it belongs to none, which is the default.
+    ///
+    /// Strategies such as [`online::Spill`] use groups to prefer some bins over others.
+    fn group(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns whether an item fits into the bin, allowing for the [`Bin::tolerance`] and
+    /// respecting the [`Bin::max_item_size`].
+    fn fits(&self, item: &impl Item) -> bool {
+        item.size() <= self.max_item_size()
+            && self.load() + item.size() <= Self::capacity() + Self::tolerance()
+    }
 }
 
 /// An item that can be packed into a bin.
 pub trait Item {
     /// Returns the size of the item.
     fn size(&self) -> usize;
+    /// Returns the value of packing the item, which defaults to its size.
+    ///
+    /// Only strategies that cannot pack every item, such as [`offline::pack_most_valuable`],
+    /// consult this to decide which items to pack.
+    fn value(&self) -> usize {
+        self.size()
+    }
+    /// Returns how long the item is expected to stay in its bin, in any unit, or `None` if that is
+    /// unknown, which is the default.
+    ///
+    /// Only departure-aware strategies, such as [`online::LifetimeFit`], consult this to keep
+    /// items that leave around the same time together.
+    fn lifetime(&self) -> Option<usize> {
+        None
+    }
+    /// Returns the owner of the item, such as a tenant, or `None` if it has none, which is the
+    /// default.
+    ///
+    /// Only [`online::Quotas`] consults this, to limit how much of a bin each owner can take.
+    fn owner(&self) -> Option<usize> {
+        None
+    }
+    /// Returns an identifier of the item, or `None` if it has none, which is the default.
+    ///
+    /// Only [`constraints::Constraints`] that involve particular items, such as conflicts,
+    /// consult this.
+    fn id(&self) -> Option<usize> {
+        None
+    }
+    /// Returns the index of the item among the items being recorded into a [`Packing`], which
+    /// only the wrapper the crate packs them in knows.
+    #[doc(hidden)]
+    fn index(&self, _: packing::Internal) -> Option<usize> {
+        None
+    }
+}
+
+/// Plain sizes can be packed directly.
+impl Item for usize {
+    fn size(&self) -> usize {
+        *self
+    }
 }
@@ -0,0 +1,259 @@
+//! Packs item sizes read from a file or standard input and prints the resulting assignment.
+
+use std::fmt::Write as _;
+use std::io::Read;
+use std::process::ExitCode;
+
+use bin_packing::{bounds, Algorithm, Packing};
+
+const USAGE: &str = "\
+Usage: bin-packing [OPTIONS] [FILE]
+
+Packs item sizes read from FILE, or standard input if omitted, into bins.
+
+Options:
+  -c, --capacity <N>      Bin capacity, required unless the input is in BPPLIB format
+  -s, --strategy <NAME>   Strategy to pack with [default: ffd]
+  -f, --format <FORMAT>   Input format: plain, csv or bpplib [default: plain]
+  -o, --output <OUTPUT>   Output format: text or json [default: text]
+  -h, --help              Print this help
+
+Strategies: ff, nf, bf, wf, awf, ffd, bfd, wfd, mffd, mbfd, b2f, seq
+
+Input formats:
+  plain   sizes separated by whitespace
+  csv     one item per line, with its size in the first column and an optional header
+  bpplib  the number of items, the capacity, then one size per line";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Plain,
+    Csv,
+    Bpplib,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+struct Args {
+    capacity: Option<usize>,
+    algorithm: Algorithm,
+    format: Format,
+    output: Output,
+    file: Option<String>,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run() -> Result<String, String> {
+    let Some(args) = parse_args(std::env::args().skip(1))? else {
+        return Ok(format!("{USAGE}\n"));
+    };
+    let mut input = String::new();
+    match &args.file {
+        Some(path) => {
+            input = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        }
+        None => {
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("stdin: {e}"))?;
+        }
+    }
+    let (sizes, file_capacity) = parse_input(&input, args.format)?;
+    let capacity = args
+        .capacity
+        .or(file_capacity)
+        .ok_or("missing --capacity")?;
+    if let Some(size) = sizes.iter().find(|&&size| size > capacity) {
+        return Err(format!("item of size {size} exceeds capacity {capacity}"));
+    }
+    let packing = args.algorithm.pack(capacity, &sizes);
+    Ok(match args.output {
+        Output::Text => text(&packing),
+        Output::Json => json(&packing, args.algorithm),
+    })
+}
+
+/// Parses command line arguments, returning `None` if help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        capacity: None,
+        algorithm: Algorithm::FirstFitDecreasing,
+        format: Format::Plain,
+        output: Output::Text,
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-c" | "--capacity" => {
+                let value = value()?;
+                parsed.capacity = Some(value.parse().map_err(|_| format!("bad capacity {value}"))?);
+            }
+            "-s" | "--strategy" => {
+                parsed.algorithm = value()?.parse().map_err(|e| format!("{e}"))?;
+            }
+            "-f" | "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "plain" => Format::Plain,
+                    "csv" => Format::Csv,
+                    "bpplib" => Format::Bpplib,
+                    other => return Err(format!("unknown format `{other}`")),
+                };
+            }
+            "-o" | "--output" => {
+                parsed.output = match value()?.as_str() {
+                    "text" => Output::Text,
+                    "json" => Output::Json,
+                    other => return Err(format!("unknown output `{other}`")),
+                };
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option {arg}"));
+            }
+            _ if parsed.file.is_none() => {
+                parsed.file = (arg != "-").then_some(arg);
+            }
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+    Ok(Some(parsed))
+}
+
+/// Parses item sizes, and the capacity if the format includes it.
+fn parse_input(input: &str, format: Format) -> Result<(Vec<usize>, Option<usize>), String> {
+    let number = |s: &str| {
+        s.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("invalid size `{}`", s.trim()))
+    };
+    match format {
+        Format::Plain => Ok((
+            input
+                .split_whitespace()
+                .map(number)
+                .collect::<Result<_, _>>()?,
+            None,
+        )),
+        Format::Csv => {
+            let mut sizes = vec![];
+            for (i, line) in input.lines().enumerate() {
+                let field = line.split(',').next().unwrap_or_default().trim();
+                if field.is_empty() {
+                    continue;
+                }
+                match number(field) {
+                    Ok(size) => sizes.push(size),
+                    // Tolerate a header line.
+                    Err(_) if i == 0 => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok((sizes, None))
+        }
+        Format::Bpplib => {
+            let mut numbers = input.split_whitespace().map(number);
+            let count = numbers.next().ok_or("missing item count")??;
+            let capacity = numbers.next().ok_or("missing capacity")??;
+            let sizes = numbers.collect::<Result<Vec<_>, _>>()?;
+            if sizes.len() != count {
+                return Err(format!("expected {count} items, found {}", sizes.len()));
+            }
+            Ok((sizes, Some(capacity)))
+        }
+    }
+}
+
+fn text(packing: &Packing) -> String {
+    let mut out = String::new();
+    for (i, (bin, load)) in packing.bins().iter().zip(packing.loads()).enumerate() {
+        let items = bin.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(
+            out,
+            "bin {i}: {load}/{} [{}]",
+            packing.capacity(),
+            items.join(" ")
+        );
+    }
+    let _ = writeln!(
+        out,
+        "bins: {}, lower bound: {}, utilization: {:.1}%",
+        packing.len(),
+        bounds::l1(packing.capacity(), packing.sizes()),
+        packing.utilization() * 100.0
+    );
+    out
+}
+
+fn json(packing: &Packing, algorithm: Algorithm) -> String {
+    let list = |values: &[usize]| {
+        let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        format!("[{}]", values.join(","))
+    };
+    let bins = packing
+        .bins()
+        .iter()
+        .zip(packing.loads())
+        .map(|(bin, load)| format!("{{\"load\":{load},\"items\":{}}}", list(bin)))
+        .collect::<Vec<_>>();
+    format!(
+        "{{\"strategy\":\"{algorithm}\",\"capacity\":{},\"bins\":[{}],\"assignment\":{},\
+         \"stats\":{{\"bins\":{},\"lower_bound\":{},\"utilization\":{}}}}}\n",
+        packing.capacity(),
+        bins.join(","),
+        list(&packing.assignment()),
+        packing.len(),
+        bounds::l1(packing.capacity(), packing.sizes()),
+        packing.utilization(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_input_formats() {
+        assert_eq!(
+            parse_input("3 4\n5", Format::Plain),
+            Ok((vec![3, 4, 5], None))
+        );
+        assert_eq!(
+            parse_input("size,name\n3,a\n4,b\n", Format::Csv),
+            Ok((vec![3, 4], None))
+        );
+        assert_eq!(
+            parse_input("2\n10\n3\n4\n", Format::Bpplib),
+            Ok((vec![3, 4], Some(10)))
+        );
+        assert!(parse_input("3\n10\n3\n4\n", Format::Bpplib).is_err());
+    }
+
+    #[test]
+    fn prints_json() {
+        let packing = Algorithm::NextFit.pack(10, &[6, 4, 5]);
+        assert_eq!(
+            json(&packing, Algorithm::NextFit),
+            "{\"strategy\":\"nf\",\"capacity\":10,\"bins\":[{\"load\":10,\"items\":[0,1]},\
+             {\"load\":5,\"items\":[2]}],\"assignment\":[0,0,1],\
+             \"stats\":{\"bins\":2,\"lower_bound\":2,\"utilization\":0.75}}\n"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Memoized packing for workloads that pack the same items over and over.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::*;
+
+/// An offline strategy that remembers the packings computed by another strategy.
+///
+/// Packings are cached by capacity and the sizes of the items regardless of their order, so
+/// packing a batch with the same mix of sizes as an earlier one reuses the earlier packing
+/// instead of running the strategy again. The cache grows with every distinct batch until
+/// [`Memoized::clear`] is called.
+///
+/// When used as an [`offline::Strategy`], only packing into an empty set of bins is cached, as the
+/// result also depends on what is already in the bins, and only for bins without a limit on the
+/// size of their items.
+pub struct Memoized<S> {
+    strategy: S,
+    cache: Mutex<HashMap<(usize, Vec<usize>), Packing>>,
+}
+
+impl<S: offline::Strategy> Memoized<S> {
+    /// Wraps a strategy with an empty cache.
+    pub fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// Packs items into bins of the given capacity and records the result, reusing the packing of
+    /// an earlier batch with the same sizes if there is one.
+    pub fn pack(&self, capacity: usize, items: &[impl Item]) -> Packing {
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| items[i].size());
+        let sorted = order.iter().map(|&i| items[i].size()).collect::<Vec<_>>();
+        let packed = self.packed(capacity, sorted);
+        let bins = packed
+            .bins()
+            .iter()
+            .map(|bin| bin.iter().map(|&i| order[i]).collect())
+            .collect();
+        let sizes = items.iter().map(Item::size).collect();
+        Packing::relabeled(capacity, sizes, bins)
+    }
+
+    /// Returns the number of cached packings.
+    pub fn len(&self) -> usize {
+        self.cache.lock().unwrap().len()
+    }
+
+    /// Returns whether nothing has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all cached packings.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Returns the packing of the sorted sizes, computing it on a cache miss.
+    fn packed(&self, capacity: usize, sorted: Vec<usize>) -> Packing {
+        let key = (capacity, sorted);
+        if let Some(packing) = self.cache.lock().unwrap().get(&key) {
+            return packing.clone();
+        }
+        // Pack without holding the lock, so a slow strategy does not block cache hits.
+        let packing = Packing::new(&self.strategy, capacity, &key.1);
+        self.cache.lock().unwrap().insert(key, packing.clone());
+        packing
+    }
+}
+
+impl<S: offline::Strategy> offline::Strategy for Memoized<S> {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        // The cached packings assume bins that accept any item that fits.
+        if !bins.is_empty() || B::default().max_item_size() < B::capacity() + B::tolerance() {
+            self.strategy.pack_all(bins, items);
+            return;
+        }
+        let assignment = self
+            .pack(B::capacity() + B::tolerance(), items)
+            .assignment();
+        offline::place(bins, items, &assignment);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::FirstFitDecreasing;
+
+    #[test]
+    fn reuses_packings_of_the_same_sizes() {
+        let memoized = Memoized::new(FirstFitDecreasing);
+        let first = memoized.pack(10, &[6, 4, 5, 5]);
+        assert_eq!(first, Packing::new(&FirstFitDecreasing, 10, &[6, 4, 5, 5]));
+
+        let second = memoized.pack(10, &[5, 4, 6, 5]);
+        assert_eq!(memoized.len(), 1);
+        assert_eq!(second.sizes(), &[5, 4, 6, 5]);
+        assert_eq!(second.len(), 2);
+        assert_eq!(second.loads(), vec![10, 10]);
+
+        memoized.pack(12, &[5, 4, 6, 5]);
+        assert_eq!(memoized.len(), 2);
+        memoized.clear();
+        assert!(memoized.is_empty());
+
+        let oversized = memoized.pack(10, &[12, 3]);
+        assert_eq!(oversized.loads(), vec![12, 3]);
+    }
+}
@@ -0,0 +1,502 @@
+//! Offline strategies that search for better packings using randomized metaheuristics.
+//!
+//! These strategies take a seed, so packing the same items with the same seed always produces the
+//! same bins. They are much slower than the greedy strategies, but the time they take can be
+//! bounded with [`offline::Strategy::with_time_limit`].
+
+use std::cmp::Reverse;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::*;
+
+/// An offline strategy that builds many randomized greedy packings, improves each with a local
+/// search and keeps the best, known as a Greedy Randomized Adaptive Search Procedure (GRASP).
+///
+/// Each packing is built by repeatedly choosing one of the largest remaining items at random and
+/// packing it into the first bin it fits, where `alpha` sets how much smaller than the largest
+/// remaining item the choice may be. The local search then tries to empty bins by moving their
+/// items into the others. The first packing is built without randomness, so the result is never
+/// worse than [`offline::FirstFitDecreasing`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grasp {
+    /// The number of packings to build.
+    pub restarts: usize,
+    /// How far below the largest remaining item the sizes of candidate items may be, as a
+    /// fraction between 0, which always picks the largest item, and 1, which picks any item.
+    pub alpha: f64,
+    /// The seed of the random number generator.
+    pub seed: u64,
+}
+
+impl offline::Strategy for Grasp {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        self.pack(bins, items, None, &mut offline::Profiler::new(false));
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        self.pack(
+            bins,
+            items,
+            Some(deadline),
+            &mut offline::Profiler::new(false),
+        )
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<offline::Phase> {
+        let mut profiler = offline::Profiler::new(true);
+        self.pack(bins, items, None, &mut profiler);
+        profiler.finish()
+    }
+}
+
+impl Grasp {
+    /// Packs items with the best packing found before the deadline, and returns whether all
+    /// restarts ran.
+    fn pack<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Option<Instant>,
+        profiler: &mut offline::Profiler,
+    ) -> bool {
+        let limits = offline::Limits::of(bins);
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+        let mut state = State::new(&sizes, &limits, bins);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let mut best: Option<State> = None;
+        let mut finished = true;
+        for restart in 0..self.restarts.max(1) {
+            if restart > 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                finished = false;
+                break;
+            }
+            let alpha = if restart == 0 { 0.0 } else { self.alpha };
+            let mut candidate = state.clone();
+            candidate.construct(&order, alpha, &mut rng);
+            profiler.phase("construct", 1);
+            candidate.improve();
+            profiler.phase("improve", 1);
+            if best.as_ref().is_none_or(|best| candidate.better_than(best)) {
+                best = Some(candidate);
+            }
+        }
+        state = best.unwrap();
+        offline::place(bins, items, &state.assignment());
+        finished
+    }
+}
+
+/// An offline strategy that searches for good packings with ant colony optimization, following
+/// Levine and Ducatelle.
+///
+/// In every iteration each ant builds a packing bin by bin, much like first fit decreasing: it
+/// starts each bin with the largest remaining item and then keeps adding items that fit, chosen at
+/// random with a preference for large items and for sizes that shared a bin in good packings
+/// before, which is learned as pheromone on pairs of sizes. The best packing of each iteration is
+/// improved with a local search and reinforces the pheromone of its pairs, while all pheromone
+/// slowly evaporates, and the best packing overall is kept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AntColony {
+    /// The number of packings built per iteration.
+    pub ants: usize,
+    /// The number of iterations.
+    pub iterations: usize,
+    /// How strongly ants prefer larger items, where 0 ignores sizes.
+    pub beta: f64,
+    /// The fraction of pheromone that evaporates per iteration, between 0 and 1.
+    pub evaporation: f64,
+    /// The seed of the random number generator.
+    pub seed: u64,
+}
+
+/// The least pheromone on any pair of sizes, which keeps every choice possible.
+const MIN_PHEROMONE: f64 = 0.01;
+
+impl offline::Strategy for AntColony {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        self.pack(bins, items, None, &mut offline::Profiler::new(false));
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        self.pack(
+            bins,
+            items,
+            Some(deadline),
+            &mut offline::Profiler::new(false),
+        )
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<offline::Phase> {
+        let mut profiler = offline::Profiler::new(true);
+        self.pack(bins, items, None, &mut profiler);
+        profiler.finish()
+    }
+}
+
+impl AntColony {
+    /// Packs items with the best packing found before the deadline, and returns whether all
+    /// iterations ran.
+    fn pack<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Option<Instant>,
+        profiler: &mut offline::Profiler,
+    ) -> bool {
+        let limits = offline::Limits::of(bins);
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+
+        // Pheromone is kept per pair of distinct sizes rather than per pair of items.
+        let mut distinct = sizes.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let class = sizes
+            .iter()
+            .map(|size| distinct.binary_search(size).unwrap())
+            .collect::<Vec<_>>();
+        let mut pheromone = vec![vec![1.0; distinct.len()]; distinct.len()];
+
+        let start = State::new(&sizes, &limits, bins);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut best: Option<State> = None;
+        let mut finished = true;
+        for iteration in 0..self.iterations.max(1) {
+            if iteration > 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                finished = false;
+                break;
+            }
+            let mut iteration_best: Option<State> = None;
+            for _ in 0..self.ants.max(1) {
+                let mut ant = start.clone();
+                self.construct(&mut ant, &order, &class, &pheromone, &mut rng);
+                if iteration_best
+                    .as_ref()
+                    .is_none_or(|best| ant.better_than(best))
+                {
+                    iteration_best = Some(ant);
+                }
+            }
+            profiler.phase("construct", self.ants.max(1));
+            let mut iteration_best = iteration_best.unwrap();
+            iteration_best.improve();
+            profiler.phase("improve", 1);
+
+            let fitness = iteration_best.fitness();
+            for row in &mut pheromone {
+                for tau in row {
+                    *tau = (*tau * (1.0 - self.evaporation)).max(MIN_PHEROMONE);
+                }
+            }
+            for bin in &iteration_best.contents {
+                for &i in bin {
+                    for &j in bin {
+                        if i != j {
+                            pheromone[class[i]][class[j]] += fitness;
+                        }
+                    }
+                }
+            }
+            if best
+                .as_ref()
+                .is_none_or(|best| iteration_best.better_than(best))
+            {
+                best = Some(iteration_best);
+            }
+            profiler.phase("pheromone", 1);
+        }
+        offline::place(bins, items, &best.unwrap().assignment());
+        finished
+    }
+
+    /// Builds a packing one bin at a time, choosing items at random according to their size and
+    /// the pheromone between them and the items already in the bin.
+    fn construct(
+        &self,
+        ant: &mut State,
+        order: &[usize],
+        class: &[usize],
+        pheromone: &[Vec<f64>],
+        rng: &mut impl Rng,
+    ) {
+        let mut remaining = order.to_vec();
+        let mut b = 0;
+        let mut weights = vec![];
+        while !remaining.is_empty() {
+            if b == ant.bins() {
+                ant.loads.push(0);
+                ant.contents.push(vec![]);
+            }
+            if ant.contents[b].is_empty() && b >= ant.fixed {
+                let largest = remaining.remove(0);
+                ant.put(largest, b);
+                continue;
+            }
+
+            weights.clear();
+            weights.extend(remaining.iter().map(|&j| {
+                if !ant.limits.fits(b, ant.loads[b], ant.sizes[j]) {
+                    return 0.0;
+                }
+                let bin = &ant.contents[b];
+                let tau = if bin.is_empty() {
+                    1.0
+                } else {
+                    bin.iter()
+                        .map(|&k| pheromone[class[j]][class[k]])
+                        .sum::<f64>()
+                        / bin.len() as f64
+                };
+                tau * (ant.sizes[j] as f64).powf(self.beta)
+            }));
+            let total = weights.iter().sum::<f64>();
+            if total <= 0.0 {
+                b += 1;
+                continue;
+            }
+            let mut target = rng.gen_range(0.0..total);
+            let mut pick = weights.iter().rposition(|&w| w > 0.0).unwrap();
+            for (k, &weight) in weights.iter().enumerate() {
+                if weight > 0.0 && target < weight {
+                    pick = k;
+                    break;
+                }
+                target -= weight;
+            }
+            let j = remaining.remove(pick);
+            ant.put(j, b);
+        }
+    }
+}
+
+/// A packing of items into bins, tracking only the items being packed and not what was in the
+/// bins before.
+#[derive(Clone)]
+struct State<'a> {
+    sizes: &'a [usize],
+    limits: &'a offline::Limits,
+    /// The number of bins that existed before, which must be kept even if they hold no item.
+    fixed: usize,
+    loads: Vec<usize>,
+    contents: Vec<Vec<usize>>,
+}
+
+impl<'a> State<'a> {
+    /// Creates an empty packing into the given bins.
+    fn new<B: Bin>(sizes: &'a [usize], limits: &'a offline::Limits, bins: &[B]) -> Self {
+        Self {
+            sizes,
+            limits,
+            fixed: bins.len(),
+            loads: bins.iter().map(Bin::load).collect(),
+            contents: vec![vec![]; bins.len()],
+        }
+    }
+
+    /// Packs items into the first bin they fit, going through them in the given order but
+    /// choosing each one at random among the remaining items no more than `alpha` smaller than
+    /// the next one.
+    fn construct(&mut self, order: &[usize], alpha: f64, rng: &mut impl Rng) {
+        let mut remaining = order.to_vec();
+        while !remaining.is_empty() {
+            let threshold = self.sizes[remaining[0]] as f64 * (1.0 - alpha);
+            let candidates = remaining.partition_point(|&i| self.sizes[i] as f64 >= threshold);
+            let i = remaining.remove(rng.gen_range(0..candidates.max(1)));
+            self.first_fit(i);
+        }
+    }
+
+    /// Packs an item into the first bin it fits, opening a new bin if there is none.
+    fn first_fit(&mut self, i: usize) {
+        let size = self.sizes[i];
+        match (0..self.loads.len()).find(|&b| self.limits.fits(b, self.loads[b], size)) {
+            Some(b) => self.put(i, b),
+            None => {
+                self.loads.push(size);
+                self.contents.push(vec![i]);
+            }
+        }
+    }
+
+    /// Packs an item into the given bin.
+    fn put(&mut self, i: usize, b: usize) {
+        self.loads[b] += self.sizes[i];
+        self.contents[b].push(i);
+    }
+
+    /// Empties the least loaded bins for as long as all their items fit into other bins.
+    fn improve(&mut self) {
+        loop {
+            let mut candidates = (self.fixed..self.loads.len()).collect::<Vec<_>>();
+            candidates.sort_by_key(|&b| self.loads[b]);
+            if !candidates.into_iter().any(|b| self.try_empty(b)) {
+                break;
+            }
+        }
+    }
+
+    /// Moves every item out of bin `b` into the fullest other bin that fits it, and removes the
+    /// bin, unless some item does not fit anywhere else. Returns whether the bin was removed.
+    fn try_empty(&mut self, b: usize) -> bool {
+        let mut loads = self.loads.clone();
+        let mut moves = vec![];
+        let mut items = self.contents[b].clone();
+        items.sort_by_key(|&i| Reverse(self.sizes[i]));
+        for i in items {
+            let size = self.sizes[i];
+            let Some(target) = (0..loads.len())
+                .filter(|&c| c != b && self.limits.fits(c, loads[c], size))
+                .max_by_key(|&c| loads[c])
+            else {
+                return false;
+            };
+            loads[target] += size;
+            moves.push((i, target));
+        }
+        self.loads = loads;
+        for (i, target) in moves {
+            self.contents[target].push(i);
+        }
+        self.loads.remove(b);
+        self.contents.remove(b);
+        true
+    }
+
+    /// Returns the number of bins used.
+    fn bins(&self) -> usize {
+        self.loads.len()
+    }
+
+    /// Returns whether this packing uses fewer bins than another, or as many but with the load
+    /// more concentrated in fewer bins, which makes it easier to improve on.
+    fn better_than(&self, other: &Self) -> bool {
+        let concentration = |loads: &[usize]| {
+            loads
+                .iter()
+                .map(|&l| (l as u128) * (l as u128))
+                .sum::<u128>()
+        };
+        (Reverse(self.bins()), concentration(&self.loads))
+            > (Reverse(other.bins()), concentration(&other.loads))
+    }
+
+    /// Returns how well the bins are filled, as the mean of the squared fill ratio of each bin,
+    /// which rewards packings with fuller bins even if they use as many bins.
+    fn fitness(&self) -> f64 {
+        if self.loads.is_empty() {
+            return 0.0;
+        }
+        let capacity = self.limits.capacity.max(1) as f64;
+        self.loads
+            .iter()
+            .map(|&load| (load as f64 / capacity).powi(2))
+            .sum::<f64>()
+            / self.loads.len() as f64
+    }
+
+    /// Returns the index of the bin each item is packed into.
+    fn assignment(&self) -> Vec<usize> {
+        let mut assignment = vec![0; self.sizes.len()];
+        for (b, bin) in self.contents.iter().enumerate() {
+            for &i in bin {
+                assignment[i] = b;
+            }
+        }
+        assignment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::Strategy as _;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn grasp_improves_on_first_fit_decreasing() {
+        let sizes = vec![4, 4, 3, 3, 3, 3];
+        let grasp = Grasp {
+            restarts: 20,
+            alpha: 0.5,
+            seed: 1,
+        };
+        let mut bins: Vec<BinImpl> = vec![];
+        grasp.pack_all(&mut bins, &mut sizes.clone());
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+
+        let mut again: Vec<BinImpl> = vec![BinImpl { used: 0 }];
+        grasp.pack_all(&mut again, &mut sizes.clone());
+        assert_eq!(again.len(), 2);
+
+        let phases = grasp.pack_all_profiled(&mut Vec::<BinImpl>::new(), &mut sizes.clone());
+        assert_eq!(phases.len(), 2);
+        assert_eq!((phases[0].name, phases[0].count), ("construct", 20));
+        assert_eq!((phases[1].name, phases[1].count), ("improve", 20));
+    }
+
+    #[test]
+    fn ant_colony_finds_tight_packings() {
+        let sizes = vec![5, 4, 4, 3, 2, 2, 6, 4, 7, 3];
+        let colony = AntColony {
+            ants: 5,
+            iterations: 10,
+            beta: 2.0,
+            evaporation: 0.1,
+            seed: 3,
+        };
+        let mut bins: Vec<BinImpl> = vec![];
+        colony.pack_all(&mut bins, &mut sizes.clone());
+        assert_eq!(bins.len(), 4);
+        assert_eq!(bins.iter().map(|b| b.used).sum::<usize>(), 40);
+
+        let mut again: Vec<BinImpl> = vec![];
+        colony.pack_all(&mut again, &mut sizes.clone());
+        assert_eq!(
+            again.iter().map(|b| b.used).collect::<Vec<_>>(),
+            bins.iter().map(|b| b.used).collect::<Vec<_>>()
+        );
+    }
+}
@@ -3,43 +3,545 @@
 //! Offline strategies have access to all items in advance and can optimize the packing process
 //! accordingly.
 
-use std::cmp::Reverse;
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
 use crate::online::Strategy as OnlineStrategy;
 use crate::*;
 
 /// An offline strategy that packs items into bins, having access all items in advance.
+///
+/// The strategies in this module sort items stably, so items that compare equal keep their relative
+/// order and packing the same items always produces the same bins.
 pub trait Strategy {
     /// Packs all items into bins, draining the items vector.
     fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>);
+
+    /// Packs all items into bins like [`Strategy::pack_all`], cutting expensive work short once
+    /// the deadline has passed, and returns whether the strategy ran to completion.
+    ///
+    /// All items are packed either way, but a strategy that did not finish falls back to cheaper
+    /// placements for the work it skipped. Strategies that cannot stop early ignore the deadline.
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        let _ = deadline;
+        self.pack_all(bins, items);
+        true
+    }
+
+    /// Packs all items into bins like [`Strategy::pack_all`], and returns how long each phase of
+    /// the strategy took.
+    ///
+    /// Strategies made up of several phases report each of them, while all others report a single
+    /// `"pack"` phase counting the items packed.
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<Phase> {
+        let mut profiler = Profiler::new(true);
+        let count = items.len();
+        self.pack_all(bins, items);
+        profiler.phase("pack", count);
+        profiler.finish()
+    }
+
+    /// Packs items from any iterator into the given bins, creating new bins as needed, and
+    /// returns the bins along with where each item went and the items that did not fit.
+    ///
+    /// Unlike [`Strategy::pack_all`], this neither needs the items collected into a vector nor
+    /// discards the assignment of items to bins.
+    fn pack_iter<B: Bin, I: Item>(
+        &self,
+        bins: Vec<B>,
+        items: impl IntoIterator<Item = I>,
+    ) -> PackingResult<B, I>
+    where
+        Self: Sized,
+    {
+        PackingResult::new(self, bins, items)
+    }
+
+    /// Wraps the strategy so that it packs within the given time limit, see [`TimeLimited`].
+    fn with_time_limit(self, limit: Duration) -> TimeLimited<Self>
+    where
+        Self: Sized,
+    {
+        TimeLimited {
+            strategy: self,
+            limit,
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for &S {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        (**self).pack_all(bins, items);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        (**self).pack_all_until(bins, items, deadline)
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<Phase> {
+        (**self).pack_all_profiled(bins, items)
+    }
+}
+
+/// How long one phase of a strategy took, as reported by [`Strategy::pack_all_profiled`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Phase {
+    /// The name of the phase, such as `"medium"` for the phase of
+    /// [`ModifiedFirstFitDecreasing`] that places medium items.
+    pub name: &'static str,
+    /// The total time spent in the phase.
+    pub duration: Duration,
+    /// How much work the phase did, which is the number of items it placed for greedy strategies
+    /// and the number of times it ran for strategies that repeat it.
+    pub count: usize,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} in {:.2?}", self.name, self.count, self.duration)
+    }
+}
+
+/// Records the phases of a strategy if enabled, and does nothing otherwise.
+pub(crate) struct Profiler {
+    phases: Option<Vec<Phase>>,
+    start: Instant,
+}
+
+impl Profiler {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            phases: enabled.then(Vec::new),
+            start: Instant::now(),
+        }
+    }
+
+    /// Ends the current phase, which started when the previous one ended, adding its time and
+    /// count to any earlier phase of the same name.
+    pub(crate) fn phase(&mut self, name: &'static str, count: usize) {
+        let Some(phases) = &mut self.phases else {
+            return;
+        };
+        let now = Instant::now();
+        let duration = now - self.start;
+        self.start = now;
+        match phases.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => {
+                phase.duration += duration;
+                phase.count += count;
+            }
+            None => phases.push(Phase {
+                name,
+                duration,
+                count,
+            }),
+        }
+    }
+
+    /// Returns the recorded phases, in the order they first ran.
+    pub(crate) fn finish(self) -> Vec<Phase> {
+        self.phases.unwrap_or_default()
+    }
+}
+
+/// Packs items into new bins using a given offline strategy and returns the bins.
+///
+/// This is the same as [`Strategy::pack_all`] starting from no bins, but accepts the items from
+/// any iterator instead of draining a vector.
+pub fn pack<B: Bin>(strategy: impl Strategy, items: impl IntoIterator<Item = impl Item>) -> Vec<B> {
+    let mut bins = vec![];
+    strategy.pack_all(&mut bins, &mut items.into_iter().collect());
+    bins
+}
+
+/// Packs items into bins using a given offline strategy, except for the items pinned to a bin by
+/// the corresponding entry of `pins`, which go into that bin, draining the items vector.
+///
+/// Pinned items are packed first, creating bins up to the highest pin as needed, and packed even
+/// if they overfill their bin. Offline strategies never move items that are already packed, so
+/// the rest are packed around them.
+pub fn pack_pinned<B: Bin, I: Item>(
+    strategy: impl Strategy,
+    bins: &mut Vec<B>,
+    items: &mut Vec<I>,
+    pins: &[Option<usize>],
+) {
+    let mut rest = vec![];
+    for (i, item) in items.drain(..).enumerate() {
+        match pins.get(i).copied().flatten() {
+            Some(pin) => place(bins, &mut vec![item], &[pin]),
+            None => rest.push(item),
+        }
+    }
+    strategy.pack_all(bins, &mut rest);
+}
+
+/// Packs a stream of items into bins using a given offline strategy, buffering at most `chunk`
+/// items at a time and packing each chunk into the bins packed so far.
+///
+/// This keeps memory use bounded for streams too large to collect, while packing close to the
+/// full offline strategy if chunks are large compared to the number of items per bin.
+pub fn pack_chunked<B: Bin, I: Item>(
+    strategy: impl Strategy,
+    bins: &mut Vec<B>,
+    items: impl IntoIterator<Item = I>,
+    chunk: usize,
+) {
+    let chunk = chunk.max(1);
+    let mut buffer = Vec::with_capacity(chunk);
+    for item in items {
+        buffer.push(item);
+        if buffer.len() == chunk {
+            strategy.pack_all(bins, &mut buffer);
+        }
+    }
+    strategy.pack_all(bins, &mut buffer);
+}
+
+/// A strategy that packs with another strategy, but stops the expensive parts once its time limit
+/// has passed, created with [`Strategy::with_time_limit`].
+pub struct TimeLimited<S> {
+    strategy: S,
+    limit: Duration,
+}
+impl<S: Strategy> TimeLimited<S> {
+    /// Packs all items into bins, draining the items vector, and returns whether the wrapped
+    /// strategy finished within the time limit.
+    pub fn pack_all_timed<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) -> bool {
+        self.pack_all_until(bins, items, Instant::now() + self.limit)
+    }
+}
+impl<S: Strategy> Strategy for TimeLimited<S> {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        self.pack_all_timed(bins, items);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        let deadline = deadline.min(Instant::now() + self.limit);
+        self.strategy.pack_all_until(bins, items, deadline)
+    }
 }
 
 /// An offline strategy that orders the item by descending size and packs them using
 /// [`crate::online::FirstFit`].
+///
+/// Use [`pack_sorted_by_key`] with [`crate::online::FirstFit`] to pack in a different order.
+///
+/// Earlier versions sorted the items by descending size but then took them from the back, so they
+/// packed the smallest item first. The largest item is now packed first, which changes the
+/// packings of existing callers.
 pub struct FirstFitDecreasing;
 impl Strategy for FirstFitDecreasing {
     fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
-        items.sort_unstable_by_key(|item| Reverse(item.size()));
-        while let Some(item) = items.pop() {
-            match crate::online::FirstFit.next_idx(bins, &item) {
-                Some(i) => bins[i].pack(item),
-                None => {
-                    bins.push(Default::default());
-                    bins.last_mut().unwrap().pack(item);
-                }
-            }
-        }
+        pack_sorted_by_key(crate::online::FirstFit, bins, items, |item| {
+            Reverse(item.size())
+        });
     }
 }
 
 /// An offline strategy that orders the item by descending size and packs them using
 /// [`crate::online::BestFit`].
+///
+/// Use [`pack_sorted_by_key`] with [`crate::online::BestFit`] to pack in a different order.
+///
+/// Earlier versions sorted the items by descending size but then took them from the back, so they
+/// packed the smallest item first. The largest item is now packed first, which changes the
+/// packings of existing callers.
 pub struct BestFitDecreasing;
 impl Strategy for BestFitDecreasing {
     fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
-        items.sort_unstable_by_key(|item| Reverse(item.size()));
-        while let Some(item) = items.pop() {
-            match crate::online::BestFit.next_idx(bins, &item) {
+        pack_sorted_by_key(crate::online::BestFit, bins, items, |item| {
+            Reverse(item.size())
+        });
+    }
+}
+
+/// An offline strategy that orders the item by descending size and packs them using
+/// [`crate::online::WorstFit`].
+///
+/// Use [`pack_sorted_by_key`] with [`crate::online::WorstFit`] to pack in a different order.
+pub struct WorstFitDecreasing;
+impl Strategy for WorstFitDecreasing {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        pack_sorted_by_key(crate::online::WorstFit, bins, items, |item| {
+            Reverse(item.size())
+        });
+    }
+}
+
+/// An offline strategy like [`FirstFitDecreasing`], which tries to avoid opening a new bin by
+/// exchanging two already packed items between bins to make room.
+///
+/// When no bin fits an item, it looks for a packed item and a smaller one in another bin whose
+/// exchange leaves enough room for the new item, and only opens a new bin if it finds none within
+/// `swap_limit` candidate exchanges. This recovers much of what a local search would gain, while
+/// bounding the extra work per item.
+pub struct EnhancedFirstFitDecreasing {
+    /// The maximum number of exchanges to consider before opening a new bin.
+    pub swap_limit: usize,
+}
+impl Strategy for EnhancedFirstFitDecreasing {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+
+        // Items already in the bins cannot be moved, so only track the ones packed here.
+        let limits = Limits::of(bins);
+        let mut loads = bins.iter().map(Bin::load).collect::<Vec<_>>();
+        let mut contents = vec![vec![]; loads.len()];
+        for i in order {
+            let size = sizes[i];
+            if let Some(b) = (0..loads.len()).find(|&b| limits.fits(b, loads[b], size)) {
+                loads[b] += size;
+                contents[b].push(i);
+            } else if !self.swap(&sizes, &mut loads, &mut contents, i, &limits) {
+                loads.push(size);
+                contents.push(vec![i]);
+            }
+        }
+
+        let mut assignment = vec![0; items.len()];
+        for (b, bin) in contents.iter().enumerate() {
+            for &i in bin {
+                assignment[i] = b;
+            }
+        }
+        place(bins, items, &assignment);
+    }
+}
+impl EnhancedFirstFitDecreasing {
+    /// Looks for two items in different bins whose exchange makes room for item `i` in one of
+    /// them, and if it finds them, exchanges them and packs the item. Returns whether it did.
+    fn swap(
+        &self,
+        sizes: &[usize],
+        loads: &mut [usize],
+        contents: &mut [Vec<usize>],
+        i: usize,
+        limits: &Limits,
+    ) -> bool {
+        let size = sizes[i];
+        let mut tries = 0;
+        for b in 0..contents.len() {
+            for k in 0..contents[b].len() {
+                let out = sizes[contents[b][k]];
+                for c in (0..contents.len()).filter(|&c| c != b) {
+                    for l in 0..contents[c].len() {
+                        let back = sizes[contents[c][l]];
+                        if back >= out {
+                            continue;
+                        }
+                        tries += 1;
+                        if tries > self.swap_limit {
+                            return false;
+                        }
+                        // The smaller item can always take the place of the larger one.
+                        if limits.fits(b, loads[b] - out + back, size)
+                            && limits.fits(c, loads[c] - back, out)
+                        {
+                            let moved = contents[b][k];
+                            contents[b][k] = contents[c][l];
+                            contents[c][l] = moved;
+                            contents[b].push(i);
+                            loads[b] = loads[b] - out + back + size;
+                            loads[c] = loads[c] - back + out;
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// An offline strategy that fills one bin at a time with the largest items that fit, then tries
+/// to fill it further by replacing its smallest item with the two unpacked items that fill it
+/// best, known as Best Two Fit (B2F).
+///
+/// Following the compound algorithm of Friesen and Langston, the packing is compared with the one
+/// [`FirstFitDecreasing`] finds and whichever needs fewer bins is used.
+pub struct BestTwoFit;
+impl Strategy for BestTwoFit {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+        let initial = bins.iter().map(Bin::load).collect::<Vec<_>>();
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+
+        let limits = Limits::of(bins);
+        let (b2f_bins, b2f) = best_two_fit(&sizes, order.clone(), &initial, &limits);
+        let mut loads = initial;
+        let mut ffd = vec![0; sizes.len()];
+        for i in order {
+            let b = match (0..loads.len()).find(|&b| limits.fits(b, loads[b], sizes[i])) {
+                Some(b) => b,
+                None => {
+                    loads.push(0);
+                    loads.len() - 1
+                }
+            };
+            loads[b] += sizes[i];
+            ffd[i] = b;
+        }
+        place(
+            bins,
+            items,
+            if b2f_bins < loads.len() { &b2f } else { &ffd },
+        );
+    }
+}
+
+/// Packs items with B2F into bins with the `initial` loads, taking the items in the given order,
+/// which must be by decreasing size. Returns the number of bins and the bin of each item.
+fn best_two_fit(
+    sizes: &[usize],
+    mut unpacked: Vec<usize>,
+    initial: &[usize],
+    limits: &Limits,
+) -> (usize, Vec<usize>) {
+    let mut assignment = vec![0; sizes.len()];
+    let mut b = 0;
+    while !unpacked.is_empty() {
+        let mut load = initial.get(b).copied().unwrap_or(0);
+        let mut packed = vec![];
+        unpacked.retain(|&i| {
+            let fits = limits.fits(b, load, sizes[i]);
+            if fits {
+                load += sizes[i];
+                packed.push(i);
+            }
+            !fits
+        });
+        // An item that fits no fresh bin gets one of its own, like in the other strategies.
+        if packed.is_empty() && b >= initial.len() {
+            let i = unpacked.remove(0);
+            load += sizes[i];
+            packed.push(i);
+        }
+
+        // Items are packed largest first, so the last one is the smallest.
+        if let Some(&smallest) = packed.last().filter(|_| load < limits.capacity) {
+            let room = limits.capacity - load + sizes[smallest];
+            let max = limits.max_item_size(b);
+            if let Some((x, y)) = best_pair(sizes, &unpacked, room, max, sizes[smallest]) {
+                let (first, second) = (unpacked[x], unpacked[y]);
+                unpacked.remove(y);
+                unpacked.remove(x);
+                packed.pop();
+                packed.extend([first, second]);
+                let at = unpacked.partition_point(|&i| sizes[i] >= sizes[smallest]);
+                unpacked.insert(at, smallest);
+            }
+        }
+        for i in packed {
+            assignment[i] = b;
+        }
+        b += 1;
+    }
+    (b.max(initial.len()), assignment)
+}
+
+/// Finds the positions of the two items in `order`, which is sorted by decreasing size, whose
+/// sizes add up to the most without exceeding `room`, as long as that is more than `least`,
+/// considering only items of at most size `max`.
+fn best_pair(
+    sizes: &[usize],
+    order: &[usize],
+    room: usize,
+    max: usize,
+    least: usize,
+) -> Option<(usize, usize)> {
+    let mut best = None;
+    let mut best_sum = least;
+    let x = order.partition_point(|&i| sizes[i] > max);
+    let (mut x, mut y) = (x, order.len().checked_sub(1)?);
+    while x < y {
+        let sum = sizes[order[x]] + sizes[order[y]];
+        if sum > room {
+            x += 1;
+        } else {
+            if sum > best_sum {
+                best_sum = sum;
+                best = Some((x, y));
+            }
+            y -= 1;
+        }
+    }
+    best
+}
+
+/// Sorts items with a comparator and packs them in that order using an online strategy, creating
+/// new bins as needed and draining the items vector.
+///
+/// The sort is stable, so items that compare equal are packed in their original order. Items that
+/// are already in order are recognised with a single pass and not sorted again, so callers that
+/// keep their items sorted do not pay for sorting on every call.
+///
+/// This generalises the decreasing strategies to arbitrary orders, for example to break ties
+/// between equally sized items by a priority.
+pub fn pack_sorted_by<B: Bin, I: Item>(
+    strategy: impl OnlineStrategy,
+    bins: &mut Vec<B>,
+    items: &mut Vec<I>,
+    mut compare: impl FnMut(&I, &I) -> Ordering,
+) {
+    if !items.is_sorted_by(|a, b| compare(a, b) != Ordering::Greater) {
+        items.sort_by(compare);
+    }
+    crate::online::pack_bins(strategy, bins, items.drain(..));
+}
+
+/// Sorts items by a key and packs them in that order using an online strategy, creating new bins
+/// as needed and draining the items vector.
+///
+/// [`FirstFitDecreasing`] is equivalent to using [`crate::online::FirstFit`] with the key
+/// `|item| Reverse(item.size())`.
+pub fn pack_sorted_by_key<B: Bin, I: Item, K: Ord>(
+    strategy: impl OnlineStrategy,
+    bins: &mut Vec<B>,
+    items: &mut Vec<I>,
+    mut key: impl FnMut(&I) -> K,
+) {
+    pack_sorted_by(strategy, bins, items, |a, b| key(a).cmp(&key(b)));
+}
+
+/// An offline strategy that packs items in their original order using [`crate::online::NextFit`].
+///
+/// Items within each bin keep their relative order and every bin is only started once the
+/// previous one is finished, so reading the bins in order yields the items in order. This is the
+/// way to split an ordered sequence into the fewest capacity-respecting runs.
+pub struct Sequential;
+impl Strategy for Sequential {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        for item in items.drain(..) {
+            match crate::online::NextFit.next_idx(bins, &item) {
                 Some(i) => bins[i].pack(item),
                 None => {
                     bins.push(Default::default());
@@ -50,28 +552,251 @@ impl Strategy for BestFitDecreasing {
     }
 }
 
+/// Splits an ordered sequence of items into the fewest consecutive runs that each fit into a bin,
+/// returning the index range of each run.
+///
+/// This computes the same partition as [`Sequential`] without needing any bins. An item too large
+/// for a bin gets a run of its own.
+pub fn linear_partition<B: Bin>(items: &[impl Item]) -> Vec<Range<usize>> {
+    let mut runs = vec![];
+    let mut start = 0;
+    let mut used = 0;
+    for (i, item) in items.iter().enumerate() {
+        if i > start && used + item.size() > B::capacity() + B::tolerance() {
+            runs.push(start..i);
+            start = i;
+            used = 0;
+        }
+        used += item.size();
+    }
+    if start < items.len() {
+        runs.push(start..items.len());
+    }
+    runs
+}
+
 /// An offline strategy that orders the item by descending size and packs them using a modified
 /// version of [`FirstFitDecreasing`], which classifies items by size and improves on regular FFD
 /// for items larger than half the bin capacity.
+///
+/// Items are classified as large, medium, small or tiny by comparing their size to the classic
+/// 1/2, 1/3 and 1/6 of the bin capacity. Use [`TunedModifiedFirstFitDecreasing`] to classify them
+/// by other fractions.
 pub struct ModifiedFirstFitDecreasing;
 impl Strategy for ModifiedFirstFitDecreasing {
     fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        TunedModifiedFirstFitDecreasing::default().pack_all(bins, items);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        TunedModifiedFirstFitDecreasing::default().pack_all_until(bins, items, deadline)
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<Phase> {
+        TunedModifiedFirstFitDecreasing::default().pack_all_profiled(bins, items)
+    }
+}
+
+/// An offline strategy like [`ModifiedFirstFitDecreasing`] that classifies items by configurable
+/// fractions of the bin capacity, which default to the classic 1/2, 1/3 and 1/6. Tuning them can
+/// help when item sizes cluster around one of these boundaries.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TunedModifiedFirstFitDecreasing {
+    /// Items larger than this fraction of the capacity are large.
+    pub large: f64,
+    /// Items larger than this fraction of the capacity, but not large, are medium.
+    pub medium: f64,
+    /// Items larger than this fraction of the capacity, but neither large nor medium, are small.
+    pub small: f64,
+}
+impl Default for TunedModifiedFirstFitDecreasing {
+    fn default() -> Self {
+        Self {
+            large: 1.0 / 2.0,
+            medium: 1.0 / 3.0,
+            small: 1.0 / 6.0,
+        }
+    }
+}
+impl Strategy for TunedModifiedFirstFitDecreasing {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        modified_first_fit_decreasing(self, bins, items, None, &mut Profiler::new(false));
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        modified_first_fit_decreasing(self, bins, items, Some(deadline), &mut Profiler::new(false))
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<Phase> {
+        let mut profiler = Profiler::new(true);
+        modified_first_fit_decreasing(self, bins, items, None, &mut profiler);
+        profiler.finish()
+    }
+}
+
+/// An offline strategy like [`ModifiedFirstFitDecreasing`], which places large, medium and small
+/// items the same way, but then packs the remaining items using [`BestFitDecreasing`].
+///
+/// Packing the leftovers into the fullest bin that fits them tends to beat both BFD and MFFD when
+/// the item sizes are skewed.
+pub struct ModifiedBestFitDecreasing;
+impl Strategy for ModifiedBestFitDecreasing {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        modified_best_fit_decreasing(bins, items, None, &mut Profiler::new(false));
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        modified_best_fit_decreasing(bins, items, Some(deadline), &mut Profiler::new(false))
+    }
+
+    fn pack_all_profiled<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+    ) -> Vec<Phase> {
+        let mut profiler = Profiler::new(true);
+        modified_best_fit_decreasing(bins, items, None, &mut profiler);
+        profiler.finish()
+    }
+}
+
+/// Packs items with MFFD, skipping the phases after the large items and going straight to the
+/// final FFD pass once the deadline has passed. Returns whether all phases ran.
+fn modified_first_fit_decreasing<B: Bin, I: Item>(
+    thresholds: &TunedModifiedFirstFitDecreasing,
+    bins: &mut Vec<B>,
+    items: &mut Vec<I>,
+    deadline: Option<Instant>,
+    profiler: &mut Profiler,
+) -> bool {
+    let mut classes = Classes::new(thresholds, bins, items, profiler);
+    let mut finished = classes.pair(bins, deadline, profiler);
+    finished &= !expired(deadline);
+    let Classes {
+        mut medium,
+        mut small,
+        mut tiny,
+    } = classes;
+
+    // Place the largest remaining items that fits in each bin.
+    if finished {
+        let remaining = medium.len() + small.len() + tiny.len();
+        tiny.sort_by_key(|item| Reverse(item.size()));
+        for bin in bins.iter_mut() {
+            while !medium.is_empty() && bin.fits(medium.first().unwrap()) {
+                bin.pack(medium.remove(0));
+            }
+            while !small.is_empty() && bin.fits(small.first().unwrap()) {
+                bin.pack(small.remove(0));
+            }
+            while !tiny.is_empty() && bin.fits(tiny.first().unwrap()) {
+                bin.pack(tiny.remove(0));
+            }
+        }
+        profiler.phase("fill", remaining - medium.len() - small.len() - tiny.len());
+    }
+
+    // Use FFD to pack the remaining items into new bins.
+    let mut remainder = medium
+        .into_iter()
+        .chain(small)
+        .chain(tiny)
+        .collect::<Vec<_>>();
+    let remaining = remainder.len();
+    FirstFitDecreasing.pack_all(bins, &mut remainder);
+    profiler.phase("remainder", remaining);
+    finished
+}
+
+/// Packs items with MBFD, going straight to the final BFD pass once the deadline has passed.
+/// Returns whether all phases ran.
+fn modified_best_fit_decreasing<B: Bin, I: Item>(
+    bins: &mut Vec<B>,
+    items: &mut Vec<I>,
+    deadline: Option<Instant>,
+    profiler: &mut Profiler,
+) -> bool {
+    let thresholds = TunedModifiedFirstFitDecreasing::default();
+    let mut classes = Classes::new(&thresholds, bins, items, profiler);
+    let finished = classes.pair(bins, deadline, profiler);
+    let mut remainder = classes
+        .medium
+        .into_iter()
+        .chain(classes.small)
+        .chain(classes.tiny)
+        .collect::<Vec<_>>();
+    let remaining = remainder.len();
+    BestFitDecreasing.pack_all(bins, &mut remainder);
+    profiler.phase("remainder", remaining);
+    finished
+}
+
+/// Returns whether the deadline, if any, has passed.
+fn expired(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// The items left after placing the large items in the modified decreasing strategies, grouped
+/// by size relative to the bin capacity.
+struct Classes<I> {
+    /// Items larger than a third of the capacity, by default.
+    medium: Vec<I>,
+    /// Items larger than a sixth of the capacity, by default.
+    small: Vec<I>,
+    /// All other items.
+    tiny: Vec<I>,
+}
+
+impl<I: Item> Classes<I> {
+    /// Sorts all large items into separate bins, adding new ones as needed, and groups the rest,
+    /// draining the items vector.
+    fn new<B: Bin>(
+        thresholds: &TunedModifiedFirstFitDecreasing,
+        bins: &mut Vec<B>,
+        items: &mut Vec<I>,
+        profiler: &mut Profiler,
+    ) -> Self {
         // Group items by size.
+        let capacity = B::capacity() as f64;
         let mut large = vec![];
         let mut medium = vec![];
         let mut small = vec![];
         let mut tiny = vec![];
         for item in items.drain(..) {
-            match item.size() {
-                s if s > B::capacity() / 2 => large.push(item),
-                s if s > B::capacity() / 3 => medium.push(item),
-                s if s > B::capacity() / 6 => small.push(item),
+            match item.size() as f64 {
+                s if s > thresholds.large * capacity => large.push(item),
+                s if s > thresholds.medium * capacity => medium.push(item),
+                s if s > thresholds.small * capacity => small.push(item),
                 _ => tiny.push(item),
             }
         }
 
         // Sort all large items into separate bins, adding new ones as needed.
-        large.sort_unstable_by_key(|item| Reverse(item.size()));
+        let count = large.len();
+        large.sort_by_key(|item| Reverse(item.size()));
         let mut idx = 0;
         for large_item in large {
             loop {
@@ -80,65 +805,853 @@ impl Strategy for ModifiedFirstFitDecreasing {
                     bins.last_mut().unwrap().pack(large_item);
                     break;
                 }
-                if large_item.size() < bins[idx].available() {
+                if bins[idx].fits(&large_item) {
                     bins[idx].pack(large_item);
                     break;
                 }
                 idx += 1;
             }
         }
+        profiler.phase("large", count);
 
+        Self {
+            medium,
+            small,
+            tiny,
+        }
+    }
+
+    /// Adds a medium item or a pair of small items to each bin where they fit, unless the
+    /// deadline passes first. Returns whether both phases ran.
+    fn pair<B: Bin>(
+        &mut self,
+        bins: &mut [B],
+        deadline: Option<Instant>,
+        profiler: &mut Profiler,
+    ) -> bool {
+        let Self { medium, small, .. } = self;
+        if expired(deadline) {
+            return false;
+        }
         // Place the largest remaining medium item that fits in each bin.
-        medium.sort_unstable_by_key(|item| Reverse(item.size()));
+        let count = medium.len();
+        medium.sort_by_key(|item| Reverse(item.size()));
         for bin in bins.iter_mut() {
-            if let Some(item_idx) = medium
-                .iter()
-                .position(|item| item.size() <= bin.available())
-            {
+            if let Some(item_idx) = medium.iter().position(|item| bin.fits(item)) {
                 bin.pack(medium.remove(item_idx));
                 if medium.is_empty() {
                     break;
                 }
             }
         }
+        profiler.phase("medium", count - medium.len());
 
+        if expired(deadline) {
+            return false;
+        }
         // Place the smallest and largest remaining small items that fit in each bin, going
         // backwards.
-        small.sort_unstable_by_key(|item| Reverse(item.size()));
+        let count = small.len();
+        small.sort_by_key(|item| Reverse(item.size()));
         for bin in bins.iter_mut().rev() {
             if small.is_empty() {
                 break;
             }
-            if small.iter().rev().take(2).map(Item::size).sum::<usize>() > bin.available() {
+            if !small.last().is_some_and(|item| bin.fits(item))
+                || bin.load() + small.iter().rev().take(2).map(Item::size).sum::<usize>()
+                    > B::capacity() + B::tolerance()
+            {
                 continue;
             }
             bin.pack(small.pop().unwrap());
-            if let Some(largest_idx) = small.iter().position(|item| item.size() <= bin.available())
-            {
+            if let Some(largest_idx) = small.iter().position(|item| bin.fits(item)) {
                 bin.pack(small.remove(largest_idx));
             }
         }
+        profiler.phase("small", count - small.len());
+        true
+    }
+}
 
-        // Place the largest remaining items that fits in each bin.
-        tiny.sort_unstable_by_key(|item| Reverse(item.size()));
-        for bin in bins.iter_mut() {
-            while !medium.is_empty() && medium.first().unwrap().size() <= bin.available() {
-                bin.pack(medium.remove(0));
+/// An offline strategy that trades the number of bins off against how evenly they are loaded.
+///
+/// For each candidate bin count, items are spread largest first onto the least loaded bin they fit
+/// into. The packing with the lowest `bins_weight * bins + imbalance_weight * imbalance` wins,
+/// where imbalance is the difference between the fullest and the emptiest bin as a fraction of the
+/// bin capacity, so it lies between 0 and 1.
+pub struct Balanced {
+    /// The cost of every bin used.
+    pub bins_weight: f64,
+    /// The cost of a full capacity's worth of imbalance.
+    pub imbalance_weight: f64,
+}
+impl Strategy for Balanced {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        self.pack(bins, items, None);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        self.pack(bins, items, Some(deadline))
+    }
+}
+impl Balanced {
+    /// Packs items with the cheapest bin count found, stopping the search at the first feasible
+    /// bin count after the deadline has passed. Returns whether all bin counts were considered.
+    fn pack<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Option<Instant>,
+    ) -> bool {
+        let capacity = B::capacity();
+        let limits = Limits::of(bins);
+        let initial = bins.iter().map(Bin::load).collect::<Vec<_>>();
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| Reverse(items[i].size()));
+        let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+
+        // Fewer bins than this can never hold all items.
+        let total = initial.iter().sum::<usize>() + sizes.iter().sum::<usize>();
+        let min_bins = bins.len().max(total.div_ceil(limits.capacity.max(1)));
+        let max_bins = bins.len() + items.len();
+
+        let mut best: Option<(f64, Vec<usize>)> = None;
+        let mut finished = true;
+        for k in min_bins..=max_bins {
+            // The imbalance term can save at most `imbalance_weight`, so once the bin term
+            // alone exceeds the best cost no larger bin count can win.
+            if let Some((cost, _)) = &best {
+                if self.bins_weight * k as f64 >= *cost {
+                    break;
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    finished = false;
+                    break;
+                }
             }
-            while !small.is_empty() && small.first().unwrap().size() <= bin.available() {
-                bin.pack(small.remove(0));
+            let mut loads = initial.clone();
+            loads.resize(k, 0);
+            let Some(assignment) = spread(&sizes, &order, &mut loads, &limits) else {
+                continue;
+            };
+            let cost = self.bins_weight * k as f64
+                + self.imbalance_weight * imbalance(&loads) as f64 / capacity as f64;
+            if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                best = Some((cost, assignment));
             }
-            while !tiny.is_empty() && tiny.first().unwrap().size() <= bin.available() {
-                bin.pack(tiny.remove(0));
+        }
+
+        // Items that fit no bin rule out every bin count, so pack them regardless.
+        match best {
+            Some((_, assignment)) => place(bins, items, &assignment),
+            None => FirstFitDecreasing.pack_all(bins, items),
+        }
+        finished
+    }
+}
+
+/// Packs the most valuable items into a limited set of bins, leaving the rest in the items vector,
+/// and returns the total value packed.
+///
+/// Items are considered in order of descending value per size and each is packed into the fullest
+/// bin that still fits it, which makes this a greedy heuristic for the multiple knapsack problem.
+pub fn pack_most_valuable<B: Bin, I: Item>(bins: &mut [B], items: &mut Vec<I>) -> usize {
+    // Compare value densities by cross-multiplying to stay in integers.
+    items.sort_by(|a, b| {
+        (b.value() as u128 * a.size() as u128).cmp(&(a.value() as u128 * b.size() as u128))
+    });
+    let mut packed = 0;
+    let mut leftovers = vec![];
+    for item in items.drain(..) {
+        match crate::online::BestFit.next_idx(bins, &item) {
+            Some(i) => {
+                packed += item.value();
+                bins[i].pack(item);
+            }
+            None => leftovers.push(item),
+        }
+    }
+    *items = leftovers;
+    packed
+}
+
+/// A point on the Pareto front between the number of bins and their imbalance, see
+/// [`pareto_front`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParetoPoint {
+    /// The number of bins used.
+    pub bins: usize,
+    /// The difference between the fullest and the emptiest bin.
+    pub imbalance: usize,
+    /// The index of the bin each item is packed into, in item order.
+    pub assignment: Vec<usize>,
+}
+
+/// Returns all packings that are not beaten on both the number of bins and the imbalance between
+/// bins by any other packing, ordered by ascending bin count.
+///
+/// This performs an exhaustive search per bin count, so it is only feasible for small instances of
+/// up to roughly a dozen items.
+pub fn pareto_front<B: Bin>(items: &[impl Item]) -> Vec<ParetoPoint> {
+    let limits = Limits::of::<B>(&[]);
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(items[i].size()));
+    let sizes = order.iter().map(|&i| items[i].size()).collect::<Vec<_>>();
+    let total = sizes.iter().sum::<usize>();
+
+    let mut front: Vec<ParetoPoint> = vec![];
+    for k in total.div_ceil(limits.capacity.max(1)).max(1)..=items.len() {
+        let mut search = BalanceSearch {
+            sizes: &sizes,
+            limits: &limits,
+            total,
+            loads: vec![0; k],
+            current: vec![0; sizes.len()],
+            best: None,
+        };
+        search.run(0);
+        let Some((imbalance, sorted_assignment)) = search.best else {
+            continue;
+        };
+        // More bins only make sense if they reduce the imbalance.
+        if front.last().is_some_and(|p| p.imbalance <= imbalance) {
+            continue;
+        }
+        let mut assignment = vec![0; items.len()];
+        for (pos, &i) in order.iter().enumerate() {
+            assignment[i] = sorted_assignment[pos];
+        }
+        front.push(ParetoPoint {
+            bins: k,
+            imbalance,
+            assignment,
+        });
+        if imbalance == 0 {
+            break;
+        }
+    }
+    front
+}
+
+/// Exhaustive search for the packing into a fixed number of bins with the least imbalance.
+struct BalanceSearch<'a> {
+    sizes: &'a [usize],
+    limits: &'a Limits,
+    total: usize,
+    loads: Vec<usize>,
+    current: Vec<usize>,
+    best: Option<(usize, Vec<usize>)>,
+}
+impl BalanceSearch<'_> {
+    fn run(&mut self, pos: usize) {
+        if pos == self.sizes.len() {
+            let imbalance = imbalance(&self.loads);
+            if self.best.as_ref().is_none_or(|(best, _)| imbalance < *best) {
+                self.best = Some((imbalance, self.current.clone()));
+            }
+            return;
+        }
+        // The emptiest bin ends up at most average full, bounding the final imbalance from below.
+        let max_load = self.loads.iter().copied().max().unwrap_or(0);
+        let bound = max_load.saturating_sub(self.total / self.loads.len());
+        if self.best.as_ref().is_some_and(|(best, _)| bound >= *best) {
+            return;
+        }
+        let size = self.sizes[pos];
+        let mut tried_empty = false;
+        for bin in 0..self.loads.len() {
+            if !self.limits.fits(bin, self.loads[bin], size) {
+                continue;
+            }
+            // Empty bins are interchangeable, so only try the first one.
+            if self.loads[bin] == 0 {
+                if tried_empty {
+                    continue;
+                }
+                tried_empty = true;
+            }
+            self.loads[bin] += size;
+            self.current[pos] = bin;
+            self.run(pos + 1);
+            self.loads[bin] -= size;
+        }
+    }
+}
+
+/// Spreads items in the given order onto the least loaded bin they fit into, returning the bin
+/// index of each item, or `None` if some item does not fit anywhere.
+fn spread(
+    sizes: &[usize],
+    order: &[usize],
+    loads: &mut [usize],
+    limits: &Limits,
+) -> Option<Vec<usize>> {
+    let mut assignment = vec![0; sizes.len()];
+    for &i in order {
+        let bin = (0..loads.len())
+            .filter(|&j| limits.fits(j, loads[j], sizes[i]))
+            .min_by_key(|&j| loads[j])?;
+        loads[bin] += sizes[i];
+        assignment[i] = bin;
+    }
+    Some(assignment)
+}
+
+/// Returns the difference between the fullest and the emptiest load.
+fn imbalance(loads: &[usize]) -> usize {
+    let max = loads.iter().copied().max().unwrap_or(0);
+    let min = loads.iter().copied().min().unwrap_or(0);
+    max - min
+}
+
+/// What bins accept, for strategies that track loads themselves instead of asking the bins.
+pub(crate) struct Limits {
+    /// The most any bin may hold, which is its capacity plus its tolerance.
+    pub(crate) capacity: usize,
+    /// The largest item each existing bin accepts.
+    max_item_sizes: Vec<usize>,
+    /// The largest item new bins accept.
+    new_max_item_size: usize,
+}
+
+impl Limits {
+    /// Returns the limits of the given bins and of any new bins.
+    pub(crate) fn of<B: Bin>(bins: &[B]) -> Self {
+        Self {
+            capacity: B::capacity() + B::tolerance(),
+            max_item_sizes: bins.iter().map(Bin::max_item_size).collect(),
+            new_max_item_size: B::default().max_item_size(),
+        }
+    }
+
+    /// Returns the limits of bins that accept any items up to the given capacity.
+    pub(crate) fn capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            max_item_sizes: vec![],
+            new_max_item_size: usize::MAX,
+        }
+    }
+
+    /// Returns the largest item the bin with the given index accepts.
+    pub(crate) fn max_item_size(&self, bin: usize) -> usize {
+        self.max_item_sizes
+            .get(bin)
+            .copied()
+            .unwrap_or(self.new_max_item_size)
+    }
+
+    /// Returns whether all bins accept any item up to the capacity.
+    pub(crate) fn unrestricted(&self) -> bool {
+        self.new_max_item_size >= self.capacity
+            && self.max_item_sizes.iter().all(|&max| max >= self.capacity)
+    }
+
+    /// Returns whether an item of the given size fits into an empty bin, whether existing or new.
+    pub(crate) fn accepts(&self, size: usize) -> bool {
+        size <= self.capacity
+            && (size <= self.new_max_item_size
+                || self.max_item_sizes.iter().any(|&max| size <= max))
+    }
+
+    /// Returns whether an item of the given size fits into the bin with the given index and load.
+    pub(crate) fn fits(&self, bin: usize, load: usize, size: usize) -> bool {
+        load + size <= self.capacity && size <= self.max_item_size(bin)
+    }
+}
+
+/// Packs each item into the bin at the corresponding index of `assignment`, creating new bins as
+/// needed and draining the items vector.
+pub(crate) fn place<B: Bin>(bins: &mut Vec<B>, items: &mut Vec<impl Item>, assignment: &[usize]) {
+    for (item, &i) in items.drain(..).zip(assignment) {
+        if bins.len() <= i {
+            bins.resize_with(i + 1, Default::default);
+        }
+        bins[i].pack(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            assert!(item.size() <= self.available(), "item too large");
+            self.used += item.size();
+        }
+    }
+
+    struct ItemImpl {
+        size: usize,
+    }
+    impl ItemImpl {
+        fn new(size: usize) -> Self {
+            ItemImpl { size }
+        }
+    }
+    impl Item for ItemImpl {
+        fn size(&self) -> usize {
+            self.size
+        }
+    }
+
+    struct ValuedItem {
+        size: usize,
+        value: usize,
+    }
+    impl Item for ValuedItem {
+        fn size(&self) -> usize {
+            self.size
+        }
+        fn value(&self) -> usize {
+            self.value
+        }
+    }
+
+    /// A bin that records the values of the items packed into it, in order.
+    #[derive(Default)]
+    struct RecordingBin {
+        values: Vec<usize>,
+        used: usize,
+    }
+    impl Bin for RecordingBin {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+            self.values.push(item.value());
+        }
+    }
+
+    fn items(sizes: &[usize]) -> Vec<ItemImpl> {
+        sizes.iter().copied().map(ItemImpl::new).collect()
+    }
+
+    #[test]
+    fn strategies_allow_for_tolerance() {
+        #[derive(Default)]
+        struct TolerantBin {
+            used: usize,
+        }
+        impl Bin for TolerantBin {
+            fn capacity() -> usize {
+                10
+            }
+            fn available(&self) -> usize {
+                Self::capacity().saturating_sub(self.used)
+            }
+            fn pack(&mut self, item: impl Item) {
+                self.used += item.size();
+            }
+            fn load(&self) -> usize {
+                self.used
+            }
+            fn tolerance() -> usize {
+                1
+            }
+        }
+
+        let sizes = [6, 5, 5, 6, 1];
+        let used = |bins: Vec<TolerantBin>| bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(
+            used(pack(FirstFitDecreasing, items(&sizes))),
+            vec![11, 11, 1]
+        );
+        assert_eq!(
+            used(pack(
+                EnhancedFirstFitDecreasing { swap_limit: 10 },
+                items(&sizes)
+            )),
+            vec![11, 11, 1]
+        );
+        assert_eq!(used(pack(BestTwoFit, items(&sizes))), vec![11, 11, 1]);
+        assert_eq!(
+            used(pack(ModifiedFirstFitDecreasing, items(&sizes))),
+            vec![11, 11, 1]
+        );
+    }
+
+    #[test]
+    fn strategies_respect_max_item_size() {
+        struct LimitedBin {
+            used: usize,
+            max: usize,
+        }
+        impl Default for LimitedBin {
+            fn default() -> Self {
+                Self { used: 0, max: 4 }
             }
         }
+        impl Bin for LimitedBin {
+            fn capacity() -> usize {
+                10
+            }
+            fn available(&self) -> usize {
+                Self::capacity() - self.used
+            }
+            fn pack(&mut self, item: impl Item) {
+                self.used += item.size();
+            }
+            fn max_item_size(&self) -> usize {
+                self.max
+            }
+        }
+
+        let bins = || vec![LimitedBin::default(), LimitedBin { used: 0, max: 10 }];
+        let used = |bins: Vec<LimitedBin>| bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        let mut ffd = bins();
+        FirstFitDecreasing.pack_all(&mut ffd, &mut items(&[4, 6]));
+        assert_eq!(used(ffd), vec![4, 6]);
+        let mut b2f = bins();
+        BestTwoFit.pack_all(&mut b2f, &mut items(&[4, 6]));
+        assert_eq!(used(b2f), vec![4, 6]);
+        let mut mffd = bins();
+        ModifiedFirstFitDecreasing.pack_all(&mut mffd, &mut items(&[6, 3]));
+        assert_eq!(used(mffd), vec![0, 9]);
+        let mut mffd = vec![LimitedBin { used: 0, max: 1 }];
+        ModifiedFirstFitDecreasing.pack_all(&mut mffd, &mut items(&[3, 2]));
+        assert_eq!(used(mffd), vec![0, 5]);
+        let mut balanced = vec![];
+        let strategy = Balanced {
+            bins_weight: 1.0,
+            imbalance_weight: 0.1,
+        };
+        strategy.pack_all(&mut balanced, &mut items(&[6, 3]));
+        assert_eq!(used(balanced), vec![9]);
+        let mut memoized = bins();
+        crate::memo::Memoized::new(FirstFitDecreasing).pack_all(&mut memoized, &mut items(&[4, 6]));
+        assert_eq!(used(memoized), vec![4, 6]);
+        let mut memoized = vec![];
+        crate::memo::Memoized::new(FirstFitDecreasing).pack_all(&mut memoized, &mut items(&[5, 5]));
+        assert_eq!(used(memoized), vec![5, 5]);
+
+        let result = PackingResult::new(&FirstFitDecreasing, vec![], items(&[3, 6]));
+        assert_eq!(used(result.bins), vec![3]);
+        assert_eq!(result.leftovers.len(), 1);
+    }
 
-        // Use FFD to pack the remaining items into new bins.
-        let mut remainder = medium
-            .into_iter()
-            .chain(small)
-            .chain(tiny)
+    #[test]
+    fn balanced_opens_bins_to_reduce_imbalance() {
+        let mut bins: Vec<BinImpl> = vec![];
+        let strategy = Balanced {
+            bins_weight: 1.0,
+            imbalance_weight: 0.1,
+        };
+        strategy.pack_all(&mut bins, &mut items(&[5, 5, 5, 5, 2]));
+        assert_eq!(bins.len(), 3);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        let strategy = Balanced {
+            bins_weight: 0.1,
+            imbalance_weight: 1.0,
+        };
+        strategy.pack_all(&mut bins, &mut items(&[5, 5, 5, 5, 2]));
+        let mut used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        used.sort();
+        assert_eq!(used, vec![5, 5, 5, 7]);
+    }
+
+    #[test]
+    fn time_limited_strategies_pack_everything() {
+        let sizes = [6, 3, 4, 2, 5, 1];
+        let mut bins: Vec<BinImpl> = vec![];
+        let strategy = ModifiedFirstFitDecreasing.with_time_limit(Duration::ZERO);
+        assert!(!strategy.pack_all_timed(&mut bins, &mut items(&sizes)));
+        assert_eq!(bins.iter().map(|b| b.used).sum::<usize>(), 21);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        let strategy = ModifiedFirstFitDecreasing.with_time_limit(Duration::from_secs(60));
+        assert!(strategy.pack_all_timed(&mut bins, &mut items(&sizes)));
+        assert_eq!(bins.len(), 3);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        let strategy = FirstFitDecreasing.with_time_limit(Duration::ZERO);
+        assert!(strategy.pack_all_timed(&mut bins, &mut items(&sizes)));
+        assert_eq!(bins.len(), 3);
+    }
+
+    #[test]
+    fn modified_best_fit_decreasing_packs_leftovers_tightly() {
+        let mut bins: Vec<BinImpl> = vec![];
+        ModifiedBestFitDecreasing.pack_all(&mut bins, &mut items(&[6, 4, 3, 2, 2, 1, 1, 1]));
+        let mut used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        used.sort();
+        assert_eq!(used, vec![10, 10]);
+    }
+
+    #[test]
+    fn modified_first_fit_decreasing_thresholds_are_configurable() {
+        let sizes = [8, 6, 3, 2, 1];
+        let mut bins: Vec<BinImpl> = vec![];
+        ModifiedFirstFitDecreasing.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(bins.len(), 3);
+
+        let tuned = TunedModifiedFirstFitDecreasing {
+            medium: 0.25,
+            small: 0.1,
+            ..Default::default()
+        };
+        let mut bins: Vec<BinImpl> = vec![];
+        tuned.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+    }
+
+    #[test]
+    fn enhanced_first_fit_decreasing_swaps_to_avoid_new_bins() {
+        let sizes = [4, 4, 3, 3, 3, 3];
+        let mut bins: Vec<BinImpl> = vec![];
+        FirstFitDecreasing.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(bins.len(), 3);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        EnhancedFirstFitDecreasing { swap_limit: 10 }.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+
+        let mut bins: Vec<BinImpl> = vec![];
+        EnhancedFirstFitDecreasing { swap_limit: 0 }.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(bins.len(), 3);
+    }
+
+    #[test]
+    fn best_two_fit_replaces_one_item_with_two() {
+        let sizes = [5, 4, 4, 3, 2, 2];
+        let mut bins: Vec<BinImpl> = vec![];
+        FirstFitDecreasing.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(bins.len(), 3);
+
+        let (bins, assignment) = best_two_fit(
+            &sizes,
+            (0..sizes.len()).collect(),
+            &[],
+            &Limits::capacity(10),
+        );
+        assert_eq!(bins, 2);
+        assert_eq!(assignment, vec![0, 1, 1, 0, 1, 0]);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        BestTwoFit.pack_all(&mut bins, &mut items(&sizes));
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+    }
+
+    #[test]
+    fn best_two_fit_gives_oversized_items_their_own_bin() {
+        let sizes = [12, 6, 4];
+        let (bins, assignment) = best_two_fit(
+            &sizes,
+            (0..sizes.len()).collect(),
+            &[],
+            &Limits::capacity(10),
+        );
+        assert_eq!(bins, 2);
+        assert_eq!(assignment, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn pack_returns_new_bins() {
+        let bins = pack::<BinImpl>(FirstFitDecreasing, [3, 9, 7, 1].map(ItemImpl::new));
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+        let bins = pack::<BinImpl>(&Sequential, (1..=5).map(ItemImpl::new));
+        assert_eq!(bins.iter().map(|b| b.used).collect::<Vec<_>>(), vec![10, 5]);
+    }
+
+    #[test]
+    fn pack_iter_accepts_any_iterator() {
+        let result =
+            BestFitDecreasing.pack_iter::<BinImpl, _>(vec![], [3, 9, 7, 1].map(ItemImpl::new));
+        assert_eq!(result.assignment, vec![Some(1), Some(0), Some(1), Some(0)]);
+        assert!(result.leftovers.is_empty());
+        assert_eq!(
+            result.bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+    }
+
+    #[test]
+    fn pareto_front_trades_bins_for_balance() {
+        let front = pareto_front::<BinImpl>(&items(&[6, 4, 5, 5]));
+        let points = front
+            .iter()
+            .map(|p| (p.bins, p.imbalance))
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![(2, 0)]);
+
+        let front = pareto_front::<BinImpl>(&items(&[5, 5, 5, 5, 2]));
+        let points = front
+            .iter()
+            .map(|p| (p.bins, p.imbalance))
             .collect::<Vec<_>>();
-        FirstFitDecreasing.pack_all(bins, &mut remainder);
+        assert_eq!(points, vec![(3, 5), (4, 2)]);
+    }
+
+    #[test]
+    fn pack_most_valuable_prefers_dense_items() {
+        let mut bins = vec![BinImpl::default()];
+        let mut items = vec![
+            ValuedItem { size: 6, value: 1 },
+            ValuedItem { size: 5, value: 5 },
+            ValuedItem { size: 5, value: 4 },
+        ];
+        assert_eq!(pack_most_valuable(&mut bins, &mut items), 9);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].size, 6);
+    }
+
+    #[test]
+    fn pack_pinned_packs_around_pins() {
+        let mut bins: Vec<BinImpl> = vec![];
+        let pins = [None, Some(1), None, None];
+        pack_pinned(
+            FirstFitDecreasing,
+            &mut bins,
+            &mut items(&[2, 3, 8, 7]),
+            &pins,
+        );
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![10, 10]);
+    }
+
+    #[test]
+    fn pack_chunked_packs_each_chunk_offline() {
+        let mut bins: Vec<BinImpl> = vec![];
+        pack_chunked(FirstFitDecreasing, &mut bins, items(&[2, 3, 8, 7]), 2);
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![5, 8, 7]);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        pack_chunked(FirstFitDecreasing, &mut bins, items(&[2, 3, 8, 7]), 4);
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![10, 10]);
+    }
+
+    #[test]
+    fn sorted_items_are_not_sorted_again() {
+        let mut bins: Vec<BinImpl> = vec![];
+        let mut comparisons = 0;
+        pack_sorted_by(
+            crate::online::FirstFit,
+            &mut bins,
+            &mut items(&[8, 7, 3, 2]),
+            |a, b| {
+                comparisons += 1;
+                b.size().cmp(&a.size())
+            },
+        );
+        assert_eq!(comparisons, 3);
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![10, 10]);
+    }
+
+    #[test]
+    fn sequential_keeps_runs_in_order() {
+        let mut bins: Vec<BinImpl> = vec![];
+        Sequential.pack_all(&mut bins, &mut items(&[4, 4, 5, 1, 9, 2]));
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![8, 6, 9, 2]);
+    }
+
+    #[test]
+    fn linear_partition_splits_into_runs() {
+        let runs = linear_partition::<BinImpl>(&items(&[4, 4, 5, 1, 9, 2]));
+        assert_eq!(runs, vec![0..2, 2..4, 4..5, 5..6]);
+        assert!(linear_partition::<BinImpl>(&items(&[])).is_empty());
+    }
+
+    #[test]
+    fn linear_partition_gives_oversized_items_their_own_run() {
+        let runs = linear_partition::<BinImpl>(&items(&[12, 3]));
+        assert_eq!(runs, vec![0..1, 1..2]);
+        let runs = linear_partition::<BinImpl>(&items(&[3, 12, 3, 4]));
+        assert_eq!(runs, vec![0..1, 1..2, 2..4]);
+    }
+
+    #[test]
+    fn decreasing_strategies_pack_largest_items_first() {
+        let mut bins: Vec<BinImpl> = vec![];
+        FirstFitDecreasing.pack_all(&mut bins, &mut items(&[2, 3, 8, 7]));
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![10, 10]);
+
+        let mut bins: Vec<BinImpl> = vec![];
+        WorstFitDecreasing.pack_all(&mut bins, &mut items(&[2, 3, 8, 7]));
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![10, 10]);
+    }
+
+    #[test]
+    fn decreasing_strategies_pack_in_descending_order() {
+        let sizes = [2, 3, 8, 7];
+        let valued = || {
+            sizes
+                .iter()
+                .enumerate()
+                .map(|(value, &size)| ValuedItem { size, value })
+                .collect::<Vec<_>>()
+        };
+        let mut bins: Vec<RecordingBin> = vec![];
+        FirstFitDecreasing.pack_all(&mut bins, &mut valued());
+        let order = bins.iter().map(|b| b.values.clone()).collect::<Vec<_>>();
+        assert_eq!(order, vec![vec![2, 0], vec![3, 1]]);
+
+        let mut bins: Vec<RecordingBin> = vec![];
+        BestFitDecreasing.pack_all(&mut bins, &mut valued());
+        let order = bins.iter().map(|b| b.values.clone()).collect::<Vec<_>>();
+        assert_eq!(order, vec![vec![2, 0], vec![3, 1]]);
+    }
+
+    #[test]
+    fn pack_sorted_by_key_uses_custom_order() {
+        let mut bins: Vec<BinImpl> = vec![];
+        pack_sorted_by_key(
+            crate::online::FirstFit,
+            &mut bins,
+            &mut items(&[2, 8, 3, 7]),
+            |item| item.size(),
+        );
+        let used = bins.iter().map(|b| b.used).collect::<Vec<_>>();
+        assert_eq!(used, vec![5, 7, 8]);
+    }
+
+    #[test]
+    fn decreasing_strategies_keep_ties_in_order() {
+        let mut bins: Vec<RecordingBin> = vec![];
+        let mut items = (0..5)
+            .map(|value| ValuedItem { size: 5, value })
+            .chain([ValuedItem { size: 6, value: 5 }])
+            .collect();
+        FirstFitDecreasing.pack_all(&mut bins, &mut items);
+        let values = bins.iter().map(|b| b.values.clone()).collect::<Vec<_>>();
+        assert_eq!(values, vec![vec![5], vec![0, 1], vec![2, 3], vec![4]]);
     }
 }
@@ -3,8 +3,26 @@
 //! Online strategies pack items into bins as they arrive, without knowing the sizes of future
 //! items. Consequently, the API allows for sorting one item at a time.
 
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::{Rng, SeedableRng};
+
 use super::*;
 
+/// Packs items into new bins using a given online strategy and returns the bins.
+///
+/// This is the same as [`pack_bins`] starting from no bins, which makes it convenient to use at the
+/// end of an iterator pipeline.
+pub fn pack<B: Bin>(strategy: impl Strategy, items: impl IntoIterator<Item = impl Item>) -> Vec<B> {
+    let mut bins = vec![];
+    pack_bins(strategy, &mut bins, items);
+    bins
+}
+
 /// Packs bins with items using a given online strategy, creating new bins as needed.
 ///
 /// This is a convenience function to pack a lot of items at once.
@@ -14,7 +32,6 @@ pub fn pack_bins<B: Bin>(
     items: impl IntoIterator<Item = impl Item>,
 ) {
     for item in items {
-        debug_assert!(item.size() <= B::capacity());
         if let Some(i) = strategy.next_idx(bins, &item) {
             bins[i].pack(item);
         } else {
@@ -34,7 +51,6 @@ pub fn pack_existing_bins<B: Bin>(
     items: impl IntoIterator<Item = impl Item>,
 ) {
     for item in items {
-        debug_assert!(item.size() <= B::capacity());
         if let Some(i) = strategy.next_idx(bins, &item) {
             bins[i].pack(item);
         } else {
@@ -43,10 +59,235 @@ pub fn pack_existing_bins<B: Bin>(
     }
 }
 
+/// Packs an item into the first `len` bins of caller-provided storage using a given online
+/// strategy, opening the next bin of the storage if needed, and returns the index of the bin.
+///
+/// This never allocates unless the strategy or bins do, which [`FirstFit`], [`NextFit`],
+/// [`BestFit`], [`WorstFit`] and [`AlmostWorstFit`] do not, so it suits latency-sensitive and
+/// embedded uses. Bins past `len` are expected to be empty. If the item needs a new bin but the
+/// storage is full, it is handed back.
+pub fn pack_in_place<B: Bin, I: Item>(
+    strategy: impl Strategy,
+    bins: &mut [B],
+    len: &mut usize,
+    item: I,
+) -> Result<usize, I> {
+    let i = match strategy.next_idx(&bins[..*len], &item) {
+        Some(i) => i,
+        None if *len < bins.len() => {
+            *len += 1;
+            *len - 1
+        }
+        None => return Err(item),
+    };
+    bins[i].pack(item);
+    Ok(i)
+}
+
+/// Packing for iterators of items, so packing can finish an iterator pipeline.
+///
+/// Items are packed in the order the iterator yields them, for example
+/// `sizes.into_iter().filter(|&s| s > 0).pack_into::<MyBin>(BestFit)`.
+pub trait PackExt: Iterator + Sized
+where
+    Self::Item: Item,
+{
+    /// Packs the items into new bins using an online strategy and returns the bins.
+    fn pack_into<B: Bin>(self, strategy: impl Strategy) -> Vec<B> {
+        pack(strategy, self)
+    }
+
+    /// Packs the items into bins of the given capacity using an online strategy and returns the
+    /// index of the bin each item is packed into, in the order of the items.
+    fn pack_assignments(self, strategy: impl Strategy, capacity: usize) -> Vec<usize> {
+        crate::packing::with_capacity(capacity, || {
+            let mut bins = Vec::<crate::packing::Recorder>::new();
+            self.map(|item| {
+                let i = strategy.next_idx(&bins, &item).unwrap_or_else(|| {
+                    bins.push(Default::default());
+                    bins.len() - 1
+                });
+                bins[i].pack(item);
+                i
+            })
+            .collect()
+        })
+    }
+}
+
+impl<T: Iterator> PackExt for T where T::Item: Item {}
+
+/// Packs items semi-online, holding each arriving item back for at most `deadline` further
+/// arrivals before packing it with an online strategy.
+///
+/// Items are packed once their deadline is reached, oldest first. Whenever that opens a new bin,
+/// the bin is also filled with buffered items that fit, largest first, which packs tighter than
+/// an online strategy can while still bounding how long any item waits.
+pub struct DeadlineBuffer<S, I> {
+    strategy: S,
+    deadline: usize,
+    pending: VecDeque<(usize, I)>,
+    arrivals: usize,
+}
+impl<S: Strategy, I: Item> DeadlineBuffer<S, I> {
+    /// Creates an empty buffer that packs items with the given strategy within `deadline`
+    /// arrivals.
+    pub fn new(strategy: S, deadline: usize) -> Self {
+        Self {
+            strategy,
+            deadline,
+            pending: VecDeque::new(),
+            arrivals: 0,
+        }
+    }
+
+    /// Returns the number of items waiting to be packed.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Buffers an item, then packs all items whose deadline has been reached into the bins.
+    pub fn push<B: Bin>(&mut self, bins: &mut Vec<B>, item: I) {
+        self.pending
+            .push_back((self.arrivals + self.deadline, item));
+        while self
+            .pending
+            .front()
+            .is_some_and(|&(due, _)| due <= self.arrivals)
+        {
+            self.pack_next(bins);
+        }
+        self.arrivals += 1;
+    }
+
+    /// Packs all remaining items into the bins, oldest first.
+    pub fn finish<B: Bin>(&mut self, bins: &mut Vec<B>) {
+        while !self.pending.is_empty() {
+            self.pack_next(bins);
+        }
+    }
+
+    /// Packs the oldest item, filling any bin it opens from the buffer.
+    fn pack_next<B: Bin>(&mut self, bins: &mut Vec<B>) {
+        let Some((_, item)) = self.pending.pop_front() else {
+            return;
+        };
+        if let Some(i) = self.strategy.next_idx(bins, &item) {
+            bins[i].pack(item);
+            return;
+        }
+        let mut bin = B::default();
+        bin.pack(item);
+        let mut pending = self.pending.drain(..).map(Some).collect::<Vec<_>>();
+        let mut order = (0..pending.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&j| std::cmp::Reverse(pending[j].as_ref().map_or(0, |(_, i)| i.size())));
+        for j in order {
+            if pending[j].as_ref().is_some_and(|(_, item)| bin.fits(item)) {
+                bin.pack(pending[j].take().unwrap().1);
+            }
+        }
+        self.pending.extend(pending.into_iter().flatten());
+        bins.push(bin);
+    }
+}
+
+/// Where an item would be packed, as reported by [`peek_placement`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlacementPreview {
+    /// The index of the bin the item would be packed into.
+    pub idx: usize,
+    /// The available capacity of that bin after packing the item.
+    pub available: usize,
+}
+
+/// Reports which bin a given online strategy would pack an item into, and how much capacity that
+/// bin would have left, without packing it.
+///
+/// Returns `None` if the strategy does not find a suitable bin, in which case [`pack_bins`] would
+/// open a new one.
+pub fn peek_placement(
+    strategy: impl Strategy,
+    bins: &[impl Bin],
+    item: &impl Item,
+) -> Option<PlacementPreview> {
+    let idx = strategy.next_idx(bins, item)?;
+    Some(PlacementPreview {
+        idx,
+        available: bins[idx].available().saturating_sub(item.size()),
+    })
+}
+
 /// An online strategy for packing items into bins, inspecting one item at a time.
 pub trait Strategy {
     /// Returns the index of the next bin to pack the item into, or `None` if no bin is suitable.
     fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize>;
+
+    /// Explains why the strategy would or would not pack the item into each bin, by bin index.
+    ///
+    /// The default only rejects bins the item does not fit into, without scoring the others.
+    /// Strategies that rule out bins for other reasons or that score them override this.
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        bins.iter()
+            .map(|bin| {
+                if bin.fits(item) {
+                    Verdict::Scored {
+                        total: 0.0,
+                        scores: vec![],
+                    }
+                } else {
+                    Verdict::Rejected("capacity")
+                }
+            })
+            .collect()
+    }
+
+    /// Returns whether the strategy only looks at whether items fit into bins and at their
+    /// available capacity, which is `false` unless overridden.
+    ///
+    /// Such strategies can be given compact views of the bins that only hold these, which the
+    /// [`crate::packer::Packer`] keeps alongside its bins so that it can scan them quickly.
+    fn availability_only(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the strategy packs items into the first of the bins with the most
+    /// available capacity that they fit into, which is `false` unless overridden.
+    ///
+    /// The [`crate::packer::Packer`] keeps the bins of such strategies in a heap, so it finds that
+    /// bin without scanning all of them.
+    fn emptiest_first(&self) -> bool {
+        false
+    }
+}
+
+impl<S: Strategy> Strategy for &S {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        (**self).next_idx(bins, item)
+    }
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        (**self).explain(bins, item)
+    }
+    fn availability_only(&self) -> bool {
+        (**self).availability_only()
+    }
+    fn emptiest_first(&self) -> bool {
+        (**self).emptiest_first()
+    }
+}
+
+/// How a strategy rated a bin for an item, see [`Strategy::explain`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    /// The bin was ruled out for the named reason, such as `"capacity"` if the item does not
+    /// fit.
+    Rejected(&'static str),
+    /// The bin is suitable and got these weighted scores.
+    Scored {
+        /// The sum of the scores.
+        total: f64,
+        /// The weighted score for each named score.
+        scores: Vec<(&'static str, f64)>,
+    },
 }
 
 /// An online strategy that packs items into the first bin that has enough capacity.
@@ -54,46 +295,92 @@ pub struct FirstFit;
 impl Strategy for FirstFit {
     fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
         for (i, bin) in bins.iter().enumerate() {
-            if item.size() <= bin.available() {
+            if bin.fits(item) {
                 return Some(i);
             }
         }
         None
     }
+    fn availability_only(&self) -> bool {
+        true
+    }
 }
 
 /// An online strategy that packs items into the last bin if possible.
+///
+/// Packing with this strategy preserves the order of items: each bin holds a consecutive run of
+/// them, and bins are filled one after another.
 pub struct NextFit;
 impl Strategy for NextFit {
     fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
         if let Some(last_bin) = bins.last() {
-            if item.size() <= last_bin.available() {
+            if last_bin.fits(item) {
                 return Some(bins.len() - 1);
             }
         }
         None
     }
+    fn availability_only(&self) -> bool {
+        true
+    }
 }
 
 /// An online strategy that packs items into the bin with the least available capacity.
 pub struct BestFit;
 impl Strategy for BestFit {
     fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
-        let mut best_fit = None;
-        for (i, bin) in bins.iter().enumerate() {
-            if item.size() <= bin.available() {
-                match best_fit {
-                    None => best_fit = Some(i),
-                    Some(j) => {
-                        if bin.available() < bins[j].available() {
-                            best_fit = Some(i);
-                        }
-                    }
-                }
-            }
+        let idx = best_fit(bins, item);
+        #[cfg(feature = "cross-check")]
+        assert_eq!(
+            idx,
+            bins.iter()
+                .enumerate()
+                .filter(|(_, bin)| bin.fits(item))
+                .min_by_key(|(_, bin)| bin.available())
+                .map(|(i, _)| i),
+            "chunked best fit disagrees with a linear scan"
+        );
+        idx
+    }
+    fn availability_only(&self) -> bool {
+        true
+    }
+}
+
+/// How many bins [`BestFit`] ranks at a time.
+const BEST_FIT_CHUNK: usize = 8;
+
+/// Returns the first of the bins with the least available capacity that the item fits into.
+///
+/// Bins are ranked in chunks, with bins the item does not fit into ranked last, so that the
+/// compiler can rank each chunk without branches and only the chunks that improve on the best bin
+/// so far are searched for it.
+fn best_fit<B: Bin>(bins: &[B], item: &impl Item) -> Option<usize> {
+    let rank = |bin: &B| {
+        if bin.fits(item) {
+            bin.available().min(usize::MAX - 1)
+        } else {
+            usize::MAX
+        }
+    };
+    let mut best = (usize::MAX, 0);
+    for (chunk, bins) in bins.chunks(BEST_FIT_CHUNK).enumerate() {
+        let mut ranks = [usize::MAX; BEST_FIT_CHUNK];
+        for (rank_of, bin) in ranks.iter_mut().zip(bins) {
+            *rank_of = rank(bin);
+        }
+        let least = ranks
+            .iter()
+            .fold(usize::MAX, |least, &rank| least.min(rank));
+        if least < best.0 {
+            let offset = ranks
+                .iter()
+                .position(|&rank| rank == least)
+                .unwrap_or_default();
+            best = (least, chunk * BEST_FIT_CHUNK + offset);
         }
-        best_fit
     }
+    (best.0 < usize::MAX).then_some(best.1)
 }
 
 /// An online strategy that packs items into the bin with the most available capacity.
@@ -102,7 +389,7 @@ impl Strategy for WorstFit {
     fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
         let mut worst_fit = None;
         for (i, bin) in bins.iter().enumerate() {
-            if item.size() <= bin.available() {
+            if bin.fits(item) {
                 match worst_fit {
                     None => worst_fit = Some(i),
                     Some(j) => {
@@ -115,6 +402,12 @@ impl Strategy for WorstFit {
         }
         worst_fit
     }
+    fn availability_only(&self) -> bool {
+        true
+    }
+    fn emptiest_first(&self) -> bool {
+        true
+    }
 }
 
 /// An online strategy that packs items into the second most empty bin, falling back to the most
@@ -125,7 +418,7 @@ impl Strategy for AlmostWorstFit {
         let mut worst_fit = None;
         let mut almost_worst_fit = None;
         for (i, bin) in bins.iter().enumerate() {
-            if item.size() <= bin.available() {
+            if bin.fits(item) {
                 match (worst_fit, almost_worst_fit) {
                     (None, _) => worst_fit = Some(i),
                     (Some(j), None) => {
@@ -148,6 +441,644 @@ impl Strategy for AlmostWorstFit {
         }
         almost_worst_fit.or(worst_fit)
     }
+    fn availability_only(&self) -> bool {
+        true
+    }
+}
+
+/// An online strategy that packs items into the bin with the least available capacity, like
+/// [`BestFit`], but avoids leaving slivers of capacity too small for any item.
+///
+/// Leftover capacity below `min_item_size` can never be used, so bins that would be filled
+/// exactly or keep at least `min_item_size` free are preferred, and a bin that would be left
+/// with a sliver is only chosen if no other bin fits the item.
+pub struct FragmentationAware {
+    /// The size of the smallest item expected.
+    pub min_item_size: usize,
+}
+impl Strategy for FragmentationAware {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        bins.iter()
+            .enumerate()
+            .filter(|(_, bin)| bin.fits(item))
+            .min_by_key(|(_, bin)| {
+                let leftover = bin.available().saturating_sub(item.size());
+                (leftover > 0 && leftover < self.min_item_size, leftover)
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+/// An online strategy that sorts items into `k` classes by size and only packs items of the same
+/// class together, known as Harmonic-k.
+///
+/// Items larger than a `j + 1`th but at most a `j`th of the capacity are in class `j`, so up to
+/// `j` of them fit in a bin, and all items of at most a `k`th of the capacity are in class `k`.
+/// Items are packed into the most recent bin of their class, like [`NextFit`] per class.
+///
+/// The strategy remembers the class of the bins it opened, so it should be used with the same
+/// bins throughout. Bins opened for other strategies are never used.
+pub struct Harmonic {
+    k: usize,
+    /// The class of each bin, or `None` for bins opened for others.
+    classes: RefCell<Vec<Option<usize>>>,
+    /// The class of the bin opened for the last item, which had no bin to go into.
+    pending: Cell<Option<usize>>,
+}
+impl Harmonic {
+    /// Creates the strategy with `k` size classes.
+    pub fn new(k: usize) -> Self {
+        Self {
+            k: k.max(1),
+            classes: RefCell::default(),
+            pending: Cell::default(),
+        }
+    }
+
+    /// Returns the class of an item of the given size.
+    fn class(&self, size: usize, capacity: usize) -> usize {
+        (capacity / size.max(1)).clamp(1, self.k)
+    }
+}
+impl Strategy for Harmonic {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let mut classes = self.classes.borrow_mut();
+        classes.truncate(bins.len());
+        let opened = bins.len() - classes.len();
+        if opened > 0 {
+            classes.push(self.pending.take());
+            classes.resize(bins.len(), None);
+        }
+        self.pending.set(None);
+
+        let class = self.class(item.size(), capacity(bins));
+        let last = classes.iter().rposition(|&c| c == Some(class));
+        match last {
+            Some(i) if bins[i].fits(item) => Some(i),
+            _ => {
+                self.pending.set(Some(class));
+                None
+            }
+        }
+    }
+}
+
+/// An online strategy that groups items by their expected [`Item::lifetime`] and packs each item
+/// into the first bin of its group that fits, like [`FirstFit`] per group.
+///
+/// Lifetimes fall into groups by their logarithm to the given base, and items without a lifetime
+/// form a group of their own. Bins then empty out as a whole when their items leave, instead of a
+/// few long-lived items pinning down bins that are otherwise free, as happens when short-lived and
+/// long-lived items are interleaved.
+///
+/// Like [`Harmonic`], the strategy remembers the group of the bins it opened, so it should be used
+/// with the same bins throughout. Bins opened for other strategies are never used.
+pub struct LifetimeFit {
+    base: usize,
+    /// The group of each bin, or `None` for bins opened for others.
+    groups: RefCell<Vec<Option<u32>>>,
+    /// The group of the bin opened for the last item, which had no bin to go into.
+    pending: Cell<Option<u32>>,
+}
+impl LifetimeFit {
+    /// Creates the strategy, grouping lifetimes by their logarithm to `base`, which is at least 2.
+    pub fn new(base: usize) -> Self {
+        Self {
+            base: base.max(2),
+            groups: RefCell::default(),
+            pending: Cell::default(),
+        }
+    }
+
+    /// Returns the group of an item.
+    fn group(&self, item: &impl Item) -> u32 {
+        item.lifetime()
+            .map_or(u32::MAX, |lifetime| lifetime.max(1).ilog(self.base))
+    }
+}
+impl Strategy for LifetimeFit {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let mut groups = self.groups.borrow_mut();
+        groups.truncate(bins.len());
+        if bins.len() > groups.len() {
+            groups.push(self.pending.take());
+            groups.resize(bins.len(), None);
+        }
+        self.pending.set(None);
+
+        let group = self.group(item);
+        let idx = (0..bins.len()).find(|&i| groups[i] == Some(group) && bins[i].fits(item));
+        if idx.is_none() {
+            self.pending.set(Some(group));
+        }
+        idx
+    }
+}
+
+/// Wraps an online strategy to cap how much of each bin the items of any one [`Item::owner`] can
+/// take up, so that no owner monopolizes a bin.
+///
+/// The wrapped strategy sees bins where an item would exceed the quota of its owner as full.
+/// Items without an owner are not limited. The strategy keeps track of what it packed, assuming
+/// every item goes where it says, so it should be used with the same bins throughout and does not
+/// notice items being removed.
+pub struct Quotas<S> {
+    strategy: S,
+    quota: usize,
+    quotas: HashMap<usize, usize>,
+    /// How much of each bin each owner takes up.
+    usage: RefCell<Vec<HashMap<usize, usize>>>,
+    /// The owner and size of the last item, which had no bin to go into.
+    pending: Cell<Option<(usize, usize)>>,
+}
+impl<S: Strategy> Quotas<S> {
+    /// Wraps the strategy, limiting every owner to `quota` of each bin.
+    pub fn new(strategy: S, quota: usize) -> Self {
+        Self {
+            strategy,
+            quota,
+            quotas: HashMap::new(),
+            usage: RefCell::default(),
+            pending: Cell::default(),
+        }
+    }
+
+    /// Sets a different quota for the given owner.
+    pub fn with_quota(mut self, owner: usize, quota: usize) -> Self {
+        self.quotas.insert(owner, quota);
+        self
+    }
+
+    /// Returns the quota of the given owner.
+    pub fn quota(&self, owner: usize) -> usize {
+        self.quotas.get(&owner).copied().unwrap_or(self.quota)
+    }
+}
+impl<S: Strategy> Quotas<S> {
+    /// Catches up with the bins opened since the last item, returning the usage of each bin.
+    fn sync(&self, bins: usize) -> RefMut<'_, Vec<HashMap<usize, usize>>> {
+        let mut usage = self.usage.borrow_mut();
+        usage.truncate(bins);
+        if bins > usage.len() {
+            let mut opened = HashMap::new();
+            if let Some((owner, size)) = self.pending.take() {
+                opened.insert(owner, size);
+            }
+            usage.push(opened);
+            usage.resize(bins, HashMap::new());
+        }
+        self.pending.set(None);
+        usage
+    }
+
+    /// Returns whether the item would exceed the quota of its owner in a bin with the given usage.
+    fn exceeds(&self, usage: &HashMap<usize, usize>, owner: usize, item: &impl Item) -> bool {
+        usage.get(&owner).copied().unwrap_or(0) + item.size() > self.quota(owner)
+    }
+}
+impl<S: Strategy> Strategy for Quotas<S> {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let mut usage = self.sync(bins.len());
+        let Some(owner) = item.owner() else {
+            return self.strategy.next_idx(bins, item);
+        };
+        let views = mask(bins, item, |i| self.exceeds(&usage[i], owner, item));
+        let idx = self.strategy.next_idx(&views, item);
+        match idx {
+            Some(i) => *usage[i].entry(owner).or_default() += item.size(),
+            None => self.pending.set(Some((owner, item.size()))),
+        }
+        idx
+    }
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        let usage = self.sync(bins.len());
+        let Some(owner) = item.owner() else {
+            return self.strategy.explain(bins, item);
+        };
+        let blocked = (0..bins.len())
+            .map(|i| self.exceeds(&usage[i], owner, item))
+            .collect::<Vec<_>>();
+        let views = mask(bins, item, |i| blocked[i]);
+        relabel(self.strategy.explain(&views, item), |i| {
+            blocked[i].then_some("quota")
+        })
+    }
+}
+
+/// Replaces the verdicts on bins for which `reason` returns a reason with a rejection for it.
+pub(crate) fn relabel(
+    verdicts: Vec<Verdict>,
+    reason: impl Fn(usize) -> Option<&'static str>,
+) -> Vec<Verdict> {
+    verdicts
+        .into_iter()
+        .enumerate()
+        .map(|(i, verdict)| reason(i).map_or(verdict, Verdict::Rejected))
+        .collect()
+}
+
+/// Copies the bins for packing the item, marking those the item does not fit into or with an
+/// index for which `blocked` returns `true` as full.
+pub(crate) fn mask<B: Bin>(
+    bins: &[B],
+    item: &impl Item,
+    blocked: impl Fn(usize) -> bool,
+) -> Vec<Masked<B>> {
+    bins.iter()
+        .enumerate()
+        .map(|(i, bin)| Masked {
+            load: bin.load(),
+            capacity: bin.load() + bin.available(),
+            max_item_size: bin.max_item_size(),
+            group: bin.group().map(String::from),
+            blocked: !bin.fits(item) || blocked(i),
+            bin: PhantomData,
+        })
+        .collect()
+}
+
+/// A copy of a bin that can be marked as full, for strategies wrapping others.
+pub(crate) struct Masked<B> {
+    load: usize,
+    capacity: usize,
+    max_item_size: usize,
+    group: Option<String>,
+    blocked: bool,
+    bin: PhantomData<fn() -> B>,
+}
+impl<B: Bin> Default for Masked<B> {
+    fn default() -> Self {
+        Self {
+            load: 0,
+            capacity: B::capacity(),
+            max_item_size: usize::MAX,
+            group: None,
+            blocked: false,
+            bin: PhantomData,
+        }
+    }
+}
+impl<B: Bin> Bin for Masked<B> {
+    fn capacity() -> usize {
+        B::capacity()
+    }
+    fn available(&self) -> usize {
+        if self.blocked {
+            return 0;
+        }
+        self.capacity.saturating_sub(self.load)
+    }
+    fn pack(&mut self, item: impl Item) {
+        self.load += item.size();
+    }
+    fn load(&self) -> usize {
+        self.load
+    }
+    fn tolerance() -> usize {
+        B::tolerance()
+    }
+    fn max_item_size(&self) -> usize {
+        self.max_item_size
+    }
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+    fn fits(&self, item: &impl Item) -> bool {
+        !self.blocked
+            && item.size() <= self.max_item_size
+            && self.load + item.size() <= self.capacity + Self::tolerance()
+    }
+}
+
+/// Wraps an online strategy to prefer bins by their [`Bin::group`], only spilling over into the
+/// next group if no bin in the previous ones is suitable.
+///
+/// Bins in none of the groups are never used. The wrapped strategy is asked once per group until
+/// it finds a bin, so it should not keep track of the items it places, as [`Harmonic`] does.
+pub struct Spill<S> {
+    strategy: S,
+    groups: Vec<String>,
+}
+impl<S: Strategy> Spill<S> {
+    /// Wraps the strategy, trying the bins of the given groups in order.
+    pub fn new(strategy: S, groups: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            strategy,
+            groups: groups.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+impl<S: Strategy> Strategy for Spill<S> {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        self.groups.iter().find_map(|group| {
+            let views = mask(bins, item, |i| bins[i].group() != Some(group.as_str()));
+            self.strategy.next_idx(&views, item)
+        })
+    }
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        let listed = |i: usize| {
+            let group = bins[i].group();
+            self.groups.iter().any(|g| group == Some(g.as_str()))
+        };
+        let views = mask(bins, item, |i| !listed(i));
+        relabel(self.strategy.explain(&views, item), |i| {
+            (!listed(i)).then_some("group")
+        })
+    }
+}
+
+/// Returns the capacity of the given bins, for strategies that only have the bins to go by.
+pub(crate) fn capacity<B: Bin>(_: &[B]) -> usize {
+    B::capacity()
+}
+
+/// An online strategy that switches between [`FirstFit`], [`BestFit`] and [`Harmonic`] depending
+/// on which has recently wasted the least capacity per bin it opened.
+///
+/// Every `window` items the strategy measures how much the free capacity of all bins grew per
+/// new bin while the current heuristic was in charge, keeps a moving average of that waste per
+/// heuristic, and hands over to the heuristic with the lowest average, trying each one first.
+/// This adapts to streams whose size distribution changes over time, where no single heuristic
+/// does best throughout.
+pub struct Adaptive {
+    window: usize,
+    harmonic: Harmonic,
+    state: RefCell<AdaptiveState>,
+}
+
+#[derive(Default)]
+struct AdaptiveState {
+    current: usize,
+    items: usize,
+    /// The number of bins and their total free capacity when the current window started.
+    start: Option<(usize, usize)>,
+    waste: [Option<f64>; 3],
+}
+
+impl Adaptive {
+    /// Creates the strategy, reconsidering the heuristic every `window` items and packing items
+    /// of at most a `k`th of the capacity together when using [`Harmonic`].
+    pub fn new(window: usize, k: usize) -> Self {
+        Self {
+            window: window.max(1),
+            harmonic: Harmonic::new(k),
+            state: RefCell::default(),
+        }
+    }
+
+    /// Returns the index of the heuristic in use, 0 for [`FirstFit`], 1 for [`BestFit`] and 2 for
+    /// [`Harmonic`].
+    pub fn current(&self) -> usize {
+        self.state.borrow().current
+    }
+}
+
+impl Strategy for Adaptive {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let mut state = self.state.borrow_mut();
+        let free = || bins.iter().map(Bin::available).sum::<usize>();
+        if state.start.is_none() {
+            state.start = Some((bins.len(), free()));
+        }
+        if state.items == self.window {
+            let free = free();
+            let (start_bins, start_free) = state.start.unwrap();
+            let opened = bins.len().saturating_sub(start_bins);
+            if opened > 0 {
+                let waste = free.saturating_sub(start_free) as f64 / opened as f64;
+                let current = state.current;
+                state.waste[current] = Some(match state.waste[current] {
+                    Some(average) => (average + waste) / 2.0,
+                    None => waste,
+                });
+            }
+            state.current = match state.waste.iter().position(Option::is_none) {
+                Some(untried) => untried,
+                None => (0..3)
+                    .min_by(|&a, &b| state.waste[a].unwrap().total_cmp(&state.waste[b].unwrap()))
+                    .unwrap(),
+            };
+            state.items = 0;
+            state.start = Some((bins.len(), free));
+        }
+        state.items += 1;
+        let current = state.current;
+        drop(state);
+
+        // Harmonic needs to see every bin being opened to keep track of their classes.
+        let harmonic = self.harmonic.next_idx(bins, item);
+        match current {
+            0 => FirstFit.next_idx(bins, item),
+            1 => BestFit.next_idx(bins, item),
+            _ => harmonic,
+        }
+    }
+}
+
+/// An online strategy that keeps room for the large items it expects from the sizes it has seen
+/// recently, packing other items like [`BestFit`] otherwise.
+///
+/// Sizes at the given quantile of the last `window` items count as large. Smaller items are kept
+/// out of bins where they would leave too little room for a large item that would otherwise
+/// still fit, unless every bin that fits them is like that. Since the expectation follows the
+/// stream, this copes with streams that start with small items and end with large ones, where
+/// [`BestFit`] spreads the small items so that every large item needs a new bin.
+pub struct Anticipating {
+    window: usize,
+    quantile: f64,
+    recent: RefCell<VecDeque<usize>>,
+}
+impl Anticipating {
+    /// Creates the strategy, treating sizes at `quantile`, between 0 and 1, of the last `window`
+    /// items as large.
+    pub fn new(window: usize, quantile: f64) -> Self {
+        Self {
+            window: window.max(1),
+            quantile: quantile.clamp(0.0, 1.0),
+            recent: RefCell::default(),
+        }
+    }
+
+    /// Returns the size of the large items expected, or `None` before seeing any items.
+    pub fn expected(&self) -> Option<usize> {
+        let mut sizes = self.recent.borrow().iter().copied().collect::<Vec<_>>();
+        sizes.sort_unstable();
+        let rank = (self.quantile * sizes.len() as f64).ceil() as usize;
+        sizes.get(rank.saturating_sub(1)).copied()
+    }
+}
+impl Strategy for Anticipating {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let size = item.size();
+        {
+            let mut recent = self.recent.borrow_mut();
+            if recent.len() == self.window {
+                recent.pop_front();
+            }
+            recent.push_back(size);
+        }
+        let large = self.expected().unwrap_or(0);
+        let best = |keeps_room: bool| {
+            bins.iter()
+                .enumerate()
+                .filter(|(_, bin)| bin.fits(item))
+                .filter(|(_, bin)| {
+                    !keeps_room
+                        || bin.available() < large
+                        || bin.available().saturating_sub(size) >= large
+                })
+                .min_by_key(|(_, bin)| bin.available())
+                .map(|(i, _)| i)
+        };
+        if size < large {
+            best(true).or_else(|| best(false))
+        } else {
+            best(false)
+        }
+    }
+}
+
+/// An online strategy that places each item where it keeps the most room for the items it
+/// expects, given the distribution of their sizes.
+///
+/// Summed over all bins, the chance that the next item fits into each is the expected number of
+/// bins it can go into. Each item is packed into the bin where packing it lowers this the least,
+/// breaking ties like [`BestFit`], which avoids leaving gaps just too small for common sizes.
+pub struct ExpectedFit {
+    /// The distinct sizes, smallest first, and the chance that an item is at most that large.
+    cumulative: Vec<(usize, f64)>,
+}
+impl ExpectedFit {
+    /// Creates the strategy from the sizes of future items and their weights, in any order, which
+    /// need not add up to one. Sizes with a weight of zero or less are ignored.
+    pub fn new(distribution: impl IntoIterator<Item = (usize, f64)>) -> Self {
+        let mut sizes = distribution
+            .into_iter()
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect::<Vec<_>>();
+        sizes.sort_by_key(|&(size, _)| size);
+        let total = sizes.iter().map(|&(_, weight)| weight).sum::<f64>();
+        let mut cumulative = Vec::<(usize, f64)>::new();
+        let mut sum = 0.0;
+        for (size, weight) in sizes {
+            sum += weight / total;
+            match cumulative.last_mut() {
+                Some(last) if last.0 == size => last.1 = sum,
+                _ => cumulative.push((size, sum)),
+            }
+        }
+        Self { cumulative }
+    }
+
+    /// Returns the chance that the next item fits into the given available capacity.
+    pub fn fit_chance(&self, available: usize) -> f64 {
+        let at_most = self
+            .cumulative
+            .partition_point(|&(size, _)| size <= available);
+        at_most.checked_sub(1).map_or(0.0, |i| self.cumulative[i].1)
+    }
+}
+impl Strategy for ExpectedFit {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        bins.iter()
+            .enumerate()
+            .filter(|(_, bin)| bin.fits(item))
+            .map(|(i, bin)| {
+                let available = bin.available();
+                let left = available.saturating_sub(item.size());
+                let lost = self.fit_chance(available) - self.fit_chance(left);
+                (i, lost, left)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)))
+            .map(|(i, _, _)| i)
+    }
+}
+
+/// An online strategy that packs items into a bin chosen uniformly at random among those with
+/// enough capacity.
+///
+/// The random number generator is owned by the strategy, so seeding it makes packing
+/// reproducible.
+pub struct RandomFit<R> {
+    rng: RefCell<R>,
+}
+impl<R: Rng> RandomFit<R> {
+    /// Creates the strategy with the given random number generator.
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng: RefCell::new(rng),
+        }
+    }
+}
+impl RandomFit<StdRng> {
+    /// Creates the strategy with a random number generator seeded from `seed`.
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+}
+impl<R: Rng> Strategy for RandomFit<R> {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        (0..bins.len())
+            .filter(|&i| bins[i].fits(item))
+            .choose(&mut *self.rng.borrow_mut())
+    }
+}
+
+/// An online strategy that packs items into a bin chosen at random among those with enough
+/// capacity, with probability proportional to a weight computed from the available capacity of
+/// each bin.
+///
+/// Weighting by the available capacity, for example, spreads items across bins while still
+/// favouring emptier ones, which avoids the hot spots of always choosing the same bin. Bins whose
+/// weight is not a positive, finite number are never drawn. If no bin has such a weight, or the
+/// weights add up to infinity, the item goes into the first bin it fits into instead.
+pub struct WeightedRandomFit<R, W> {
+    rng: RefCell<R>,
+    weight: W,
+}
+impl<R: Rng, W: Fn(usize) -> f64> WeightedRandomFit<R, W> {
+    /// Creates the strategy with the given random number generator and weight function, which is
+    /// passed the available capacity of a bin.
+    pub fn new(rng: R, weight: W) -> Self {
+        Self {
+            rng: RefCell::new(rng),
+            weight,
+        }
+    }
+}
+impl<R: Rng, W: Fn(usize) -> f64> Strategy for WeightedRandomFit<R, W> {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        let weight = |bin: &_| {
+            let weight = if Bin::fits(bin, item) {
+                (self.weight)(Bin::available(bin))
+            } else {
+                0.0
+            };
+            if weight.is_finite() && weight > 0.0 {
+                weight
+            } else {
+                0.0
+            }
+        };
+        let total = bins.iter().map(weight).sum::<f64>();
+        if !total.is_finite() || total <= 0.0 {
+            return bins.iter().position(|bin| bin.fits(item));
+        }
+        let mut target = self.rng.borrow_mut().gen_range(0.0..total);
+        let mut last = None;
+        for (i, bin) in bins.iter().enumerate() {
+            let weight = weight(bin);
+            if weight > 0.0 {
+                if target < weight {
+                    return Some(i);
+                }
+                target -= weight;
+                last = Some(i);
+            }
+        }
+        // Rounding can leave a sliver of the total unaccounted for.
+        last
+    }
 }
 
 #[cfg(test)]
@@ -185,6 +1116,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn best_fit_matches_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for len in [0, 1, 7, 8, 9, 30] {
+            let bins = (0..len)
+                .map(|_| BinImpl {
+                    used: rng.gen_range(0..=10),
+                })
+                .collect::<Vec<_>>();
+            for size in 0..=10 {
+                let item = ItemImpl::new(size);
+                let expected = bins
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, bin)| bin.fits(&item))
+                    .min_by_key(|(_, bin)| bin.available())
+                    .map(|(i, _)| i);
+                assert_eq!(BestFit.next_idx(&bins, &item), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn deadline_buffer_fills_new_bins_from_buffer() {
+        let mut bins = Vec::<BinImpl>::new();
+        let mut buffer = DeadlineBuffer::new(NextFit, 3);
+        for size in [5, 7, 3, 5] {
+            buffer.push(&mut bins, ItemImpl::new(size));
+        }
+        assert_eq!(buffer.pending(), 2);
+        assert_eq!(bins.iter().map(|b| b.used).collect::<Vec<_>>(), vec![10]);
+        buffer.finish(&mut bins);
+        assert_eq!(buffer.pending(), 0);
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+    }
+
+    #[test]
+    fn lifetime_fit_groups_items_by_lifetime() {
+        struct Task(usize, usize);
+        impl Item for Task {
+            fn size(&self) -> usize {
+                self.0
+            }
+            fn lifetime(&self) -> Option<usize> {
+                Some(self.1)
+            }
+        }
+
+        let tasks = [
+            Task(4, 1),
+            Task(4, 100),
+            Task(4, 2),
+            Task(4, 300),
+            Task(4, 5),
+        ];
+        let bins = pack::<BinImpl>(LifetimeFit::new(10), tasks);
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![8, 8, 4]
+        );
+    }
+
+    #[test]
+    fn quotas_limit_owners_per_bin() {
+        struct Owned(usize, usize);
+        impl Item for Owned {
+            fn size(&self) -> usize {
+                self.0
+            }
+            fn owner(&self) -> Option<usize> {
+                Some(self.1)
+            }
+        }
+
+        let items = [
+            Owned(3, 0),
+            Owned(3, 0),
+            Owned(3, 0),
+            Owned(3, 1),
+            Owned(4, 1),
+        ];
+        let strategy = Quotas::new(FirstFit, 5).with_quota(1, 7);
+        let bins = pack::<BinImpl>(strategy, items);
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 3, 3]
+        );
+    }
+
+    #[test]
+    fn spill_prefers_groups_in_order() {
+        #[derive(Default)]
+        struct Pooled {
+            used: usize,
+            pool: &'static str,
+        }
+        impl Bin for Pooled {
+            fn capacity() -> usize {
+                10
+            }
+            fn available(&self) -> usize {
+                Self::capacity() - self.used
+            }
+            fn pack(&mut self, item: impl Item) {
+                self.used += item.size();
+            }
+            fn group(&self) -> Option<&str> {
+                Some(self.pool)
+            }
+        }
+
+        let bins = [(2, "hdd"), (8, "ssd"), (0, "tape"), (5, "ssd")]
+            .map(|(used, pool)| Pooled { used, pool });
+        let strategy = Spill::new(BestFit, ["ssd", "hdd"]);
+        assert_eq!(strategy.next_idx(&bins, &2), Some(1));
+        assert_eq!(strategy.next_idx(&bins, &4), Some(3));
+        assert_eq!(strategy.next_idx(&bins, &7), Some(0));
+        assert_eq!(strategy.next_idx(&bins, &9), None);
+        assert_eq!(strategy.explain(&bins, &7)[2], Verdict::Rejected("group"));
+    }
+
     #[test]
     fn first_fit_uses_first_empty_bin() {
         let bins = vec![BinImpl::default(), BinImpl::default()];
@@ -209,4 +1264,181 @@ mod tests {
         pack_bins(FirstFit, &mut bins, items);
         assert_eq!(bins.len(), 2);
     }
+
+    #[test]
+    fn peek_placement_does_not_pack() {
+        let mut bins = vec![BinImpl::default(), BinImpl::default()];
+        bins[0].used = 3;
+        let item = ItemImpl::new(5);
+        assert_eq!(
+            peek_placement(BestFit, &bins, &item),
+            Some(PlacementPreview {
+                idx: 0,
+                available: 2
+            })
+        );
+        assert_eq!(bins[0].used, 3);
+        assert_eq!(peek_placement(NextFit, &bins[..1], &ItemImpl::new(8)), None);
+    }
+
+    #[test]
+    fn random_fit_is_reproducible() {
+        let sizes = [3, 5, 2, 4, 1, 6, 2, 3];
+        let pack_seeded = |seed| {
+            let bins = pack::<BinImpl>(RandomFit::seeded(seed), sizes.map(ItemImpl::new));
+            bins.iter().map(|b| b.used).collect::<Vec<_>>()
+        };
+        assert_eq!(pack_seeded(1), pack_seeded(1));
+        assert_eq!(pack_seeded(2).iter().sum::<usize>(), 26);
+
+        let bins = vec![BinImpl { used: 8 }, BinImpl { used: 2 }];
+        assert_eq!(
+            RandomFit::seeded(3).next_idx(&bins, &ItemImpl::new(5)),
+            Some(1)
+        );
+        assert_eq!(
+            RandomFit::seeded(3).next_idx(&bins, &ItemImpl::new(9)),
+            None
+        );
+    }
+
+    #[test]
+    fn weighted_random_fit_follows_weights() {
+        let bins = vec![
+            BinImpl { used: 9 },
+            BinImpl { used: 2 },
+            BinImpl { used: 5 },
+        ];
+        let strategy =
+            WeightedRandomFit::new(StdRng::seed_from_u64(5), |available| available as f64 - 5.0);
+        for _ in 0..20 {
+            assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(1)), Some(1));
+        }
+
+        let strategy =
+            WeightedRandomFit::new(StdRng::seed_from_u64(5), |available| available as f64);
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            counts[strategy.next_idx(&bins, &ItemImpl::new(1)).unwrap()] += 1;
+        }
+        assert!(counts[1] > counts[2] && counts[2] > counts[0]);
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(9)), None);
+    }
+
+    #[test]
+    fn weighted_random_fit_skips_infinite_weights() {
+        let bins = vec![
+            BinImpl { used: 9 },
+            BinImpl { used: 2 },
+            BinImpl { used: 5 },
+        ];
+        let strategy = WeightedRandomFit::new(StdRng::seed_from_u64(5), |available| {
+            if available == 8 {
+                f64::INFINITY
+            } else {
+                1.0
+            }
+        });
+        for _ in 0..20 {
+            assert_ne!(strategy.next_idx(&bins, &ItemImpl::new(1)), Some(1));
+        }
+        let strategy = WeightedRandomFit::new(StdRng::seed_from_u64(5), |_| f64::INFINITY);
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(3)), Some(1));
+        let strategy = WeightedRandomFit::new(StdRng::seed_from_u64(5), |_| f64::NAN);
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(1)), Some(0));
+        let strategy = WeightedRandomFit::new(StdRng::seed_from_u64(5), |_| f64::MAX);
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(1)), Some(0));
+    }
+
+    #[test]
+    fn fragmentation_aware_avoids_slivers() {
+        let bins = vec![
+            BinImpl { used: 4 },
+            BinImpl { used: 1 },
+            BinImpl { used: 3 },
+        ];
+        let strategy = FragmentationAware { min_item_size: 2 };
+        assert_eq!(BestFit.next_idx(&bins, &ItemImpl::new(5)), Some(0));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(5)), Some(2));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(4)), Some(0));
+        assert_eq!(strategy.next_idx(&bins[..1], &ItemImpl::new(5)), Some(0));
+    }
+
+    #[test]
+    fn harmonic_packs_classes_separately() {
+        let strategy = Harmonic::new(3);
+        let bins = pack::<BinImpl>(&strategy, [6, 4, 2, 4, 3, 1, 2].map(ItemImpl::new));
+        assert_eq!(
+            bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![6, 8, 8]
+        );
+    }
+
+    #[test]
+    fn adaptive_tries_every_heuristic() {
+        let strategy = Adaptive::new(2, 3);
+        let mut bins = vec![];
+        pack_bins(&strategy, &mut bins, [6, 6, 3].map(ItemImpl::new));
+        assert_eq!(strategy.current(), 1);
+        pack_bins(&strategy, &mut bins, [6, 6].map(ItemImpl::new));
+        assert_eq!(strategy.current(), 2);
+        pack_bins(&strategy, &mut bins, [6, 6, 4, 4, 4].map(ItemImpl::new));
+        assert!(strategy.current() < 3);
+        let bins: Vec<BinImpl> = bins;
+        assert_eq!(bins.iter().map(|b| b.used).sum::<usize>(), 51);
+    }
+
+    #[test]
+    fn anticipating_keeps_room_for_large_items() {
+        let strategy = Anticipating::new(4, 0.75);
+        let bins = vec![BinImpl { used: 2 }, BinImpl { used: 4 }];
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(6)), Some(1));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(6)), Some(1));
+        assert_eq!(strategy.expected(), Some(6));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(2)), Some(0));
+        assert_eq!(BestFit.next_idx(&bins, &ItemImpl::new(2)), Some(1));
+
+        // Once small items dominate, it packs them like best fit.
+        for _ in 0..3 {
+            strategy.next_idx(&bins, &ItemImpl::new(1));
+        }
+        assert_eq!(strategy.expected(), Some(1));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(2)), Some(1));
+    }
+
+    #[test]
+    fn expected_fit_keeps_gaps_for_common_sizes() {
+        let strategy = ExpectedFit::new([(3, 3.0), (9, 1.0), (3, 0.0)]);
+        assert_eq!(strategy.fit_chance(2), 0.0);
+        assert_eq!(strategy.fit_chance(8), 0.75);
+        assert_eq!(strategy.fit_chance(10), 1.0);
+
+        let bins = vec![BinImpl { used: 6 }, BinImpl { used: 4 }];
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(1)), Some(0));
+        assert_eq!(strategy.next_idx(&bins, &ItemImpl::new(2)), Some(1));
+        assert_eq!(BestFit.next_idx(&bins, &ItemImpl::new(2)), Some(0));
+    }
+
+    #[test]
+    fn pack_returns_new_bins() {
+        let bins = pack::<BinImpl>(
+            FirstFit,
+            [6, 7, 4, 3]
+                .into_iter()
+                .filter(|&s| s > 3)
+                .map(ItemImpl::new),
+        );
+        assert_eq!(bins.iter().map(|b| b.used).collect::<Vec<_>>(), vec![10, 7]);
+    }
+
+    #[test]
+    fn pack_ext_packs_iterators() {
+        let bins = [6, 7, 4]
+            .into_iter()
+            .map(ItemImpl::new)
+            .pack_into::<BinImpl>(BestFit);
+        assert_eq!(bins.iter().map(|b| b.used).collect::<Vec<_>>(), vec![10, 7]);
+        let assignment = [6usize, 7, 4, 3].into_iter().pack_assignments(BestFit, 10);
+        assert_eq!(assignment, vec![0, 1, 0, 1]);
+    }
 }
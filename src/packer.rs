@@ -0,0 +1,1462 @@
+//! A stateful packer that keeps track of bins over time.
+//!
+//! Where [`crate::online::pack_bins`] packs a batch of items into bins, the [`Packer`] is meant to
+//! live alongside a long-running system, answering where each arriving item should go and
+//! supporting operations beyond plain packing, such as reserving capacity ahead of time.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::marker::PhantomData;
+
+use crate::online::{relabel, Strategy, Verdict};
+use crate::*;
+
+/// A stateful online packer.
+///
+/// The packer does not hold on to any items or bins. It keeps track of how much of each bin is in
+/// use and tells the caller where to put each item, using an online strategy to decide. Bins have
+/// the capacity of `B` unless resized, and are identified by a [`BinId`], which stays the same
+/// when other bins are removed.
+pub struct Packer<B, S> {
+    strategy: S,
+    slots: Vec<Slot<B>>,
+    /// Compact views of the slots, kept in sync with them, which strategies that only look at
+    /// availability scan instead of the slots.
+    rooms: Vec<Room<B>>,
+    /// The available capacity of open bins, for strategies that pack into the emptiest bin. Entries
+    /// go stale when bins change, and are only trusted if they match the bin.
+    emptiest: BinaryHeap<(usize, Reverse<BinId>)>,
+    index: HashMap<BinId, usize>,
+    next_bin: usize,
+    reservations: HashMap<ReservationId, Reservation>,
+    next_reservation: usize,
+    observers: Vec<Box<dyn FnMut(Event)>>,
+    closing: Option<Closing>,
+    /// The weighted average of the sizes of the items packed so far, for adaptive closing.
+    average: Option<f64>,
+    levels: Option<Levels>,
+    /// The band of utilization each bin was in when last changed, for watermark events.
+    bands: HashMap<BinId, Band>,
+    /// The band of the utilization of all bins.
+    band: Option<Band>,
+    /// The bins sealed since the last call to [`Packer::take_sealed`], in order.
+    sealed: Vec<BinId>,
+}
+
+impl<B: Bin, S: Strategy> Packer<B, S> {
+    /// Creates a packer without any bins, using the given strategy.
+    pub fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            slots: vec![],
+            rooms: vec![],
+            emptiest: BinaryHeap::new(),
+            index: HashMap::new(),
+            next_bin: 0,
+            reservations: HashMap::new(),
+            next_reservation: 0,
+            observers: vec![],
+            closing: None,
+            average: None,
+            levels: None,
+            bands: HashMap::new(),
+            band: None,
+            sealed: vec![],
+        }
+    }
+
+    /// Registers a callback that is called whenever a bin is opened or changes its state.
+    pub fn observe(&mut self, observer: impl FnMut(Event) + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Notifies observers with [`Event::Crossed`] whenever the utilization of a bin, or of all bins
+    /// together, crosses one of the watermarks, for example to scale capacity up or down from the
+    /// placement loop.
+    ///
+    /// Utilization counts reservations as used, like [`Packer::utilization`], and crossings are
+    /// only reported from the current utilization on.
+    pub fn watch(&mut self, levels: Levels) {
+        self.levels = Some(levels);
+        self.bands = self
+            .slots
+            .iter()
+            .map(|slot| (slot.id, levels.band(slot.utilization())))
+            .collect();
+        self.band = Some(levels.band(self.utilization()));
+    }
+
+    /// Seals bins as soon as packing an item into them leaves them nearly full, so that items no
+    /// longer go into bins they would hardly fit, such as hosts about to run out of memory.
+    pub fn close_below(&mut self, closing: Closing) {
+        self.closing = Some(closing);
+    }
+
+    /// Returns the available capacity below which bins are sealed, or `None` if bins are never
+    /// sealed or, with [`Closing::Adaptive`], no item has been packed yet.
+    pub fn close_threshold(&self) -> Option<usize> {
+        match self.closing? {
+            Closing::Fixed(threshold) => Some(threshold),
+            Closing::Adaptive { .. } => self.average.map(|average| average.round() as usize),
+        }
+    }
+
+    /// Returns all bins, in the order they were opened.
+    pub fn bins(&self) -> &[Slot<B>] {
+        &self.slots
+    }
+
+    /// Returns the bin with the given id, or `None` if it has been removed.
+    pub fn bin(&self, id: BinId) -> Option<&Slot<B>> {
+        self.index_of(id).map(|idx| &self.slots[idx])
+    }
+
+    /// Returns the current index of the bin with the given id in [`Packer::bins`], or `None` if it
+    /// has been removed.
+    pub fn index_of(&self, id: BinId) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// Returns the bin to pack the item into, marking its size as used and opening a new bin if the
+    /// strategy finds no suitable one.
+    pub fn pack(&mut self, item: &impl Item) -> BinId {
+        debug_assert!(item.size() <= B::capacity() + B::tolerance());
+        // The checks only look at the bins, as asking the strategy again would change the state of
+        // strategies that keep track of what they packed.
+        let idx = if self.strategy.emptiest_first() {
+            let idx = self.emptiest(item);
+            #[cfg(feature = "cross-check")]
+            assert_eq!(
+                idx,
+                self.slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| slot.fits(item))
+                    .min_by_key(|(_, slot)| Reverse(slot.available()))
+                    .map(|(i, _)| i),
+                "heap of the emptiest bins disagrees with a linear scan"
+            );
+            idx
+        } else if self.strategy.availability_only() {
+            #[cfg(feature = "cross-check")]
+            assert!(
+                self.rooms.iter().zip(&self.slots).all(|(room, slot)| {
+                    room.available() == slot.available() && room.fits(item) == slot.fits(item)
+                }),
+                "compact views of the bins disagree with the bins"
+            );
+            self.strategy.next_idx(&self.rooms, item)
+        } else {
+            self.strategy.next_idx(&self.slots, item)
+        };
+        let idx = idx.unwrap_or_else(|| self.open());
+        self.slots[idx].used += item.size();
+        self.refresh(idx);
+        let id = self.slots[idx].id;
+        if let Some(Closing::Adaptive { weight }) = self.closing {
+            let size = item.size() as f64;
+            self.average = Some(self.average.map_or(size, |average| {
+                average + weight.clamp(0.0, 1.0) * (size - average)
+            }));
+        }
+        if self
+            .close_threshold()
+            .is_some_and(|threshold| self.slots[idx].available() < threshold)
+        {
+            self.seal(id);
+        }
+        id
+    }
+
+    /// Removes an item from a bin, making its space available again. Returns `false` if the bin
+    /// does not exist or holds less than the size of the item.
+    ///
+    /// Removals are allowed in every [`BinState`].
+    pub fn remove(&mut self, id: BinId, item: &impl Item) -> bool {
+        let Some(idx) = self.index_of(id) else {
+            return false;
+        };
+        let slot = &mut self.slots[idx];
+        if slot.used < item.size() {
+            return false;
+        }
+        slot.used -= item.size();
+        self.refresh(idx);
+        true
+    }
+
+    /// Works out what removing an item from a bin would change, without removing it. Returns
+    /// `None` if the bin does not exist or holds less than the size of the item.
+    ///
+    /// `waiting` are items that could not be packed so far, such as items rejected earlier, and
+    /// the impact lists those that would fit into the bin after the removal but do not now.
+    pub fn removal_impact(
+        &self,
+        id: BinId,
+        item: &impl Item,
+        waiting: &[impl Item],
+    ) -> Option<RemovalImpact> {
+        let slot = self.bin(id)?;
+        let mut after = slot.clone();
+        after.used = slot.used.checked_sub(item.size())?;
+        Some(RemovalImpact {
+            freed: slot.available().abs_diff(after.available()),
+            available: after.available(),
+            fits: (0..waiting.len())
+                .filter(|&i| after.fits(&waiting[i]) && !slot.fits(&waiting[i]))
+                .collect(),
+        })
+    }
+
+    /// Seals an open bin, so that strategies no longer pack items into it, although reservations
+    /// it already holds can still be fulfilled. Returns `false` if the bin does not exist or is
+    /// not open.
+    pub fn seal(&mut self, id: BinId) -> bool {
+        let sealed = self.transition(id, &[BinState::Open], BinState::Sealed, Event::Sealed(id));
+        if sealed {
+            self.sealed.push(id);
+        }
+        sealed
+    }
+
+    /// Returns the bins sealed since the last call, in the order they were sealed, so that a
+    /// pipeline can process each finished bin, such as shipping a box, while packing goes on.
+    ///
+    /// Bins sealed by [`Packer::close_below`] are included. Bins that were reopened or removed
+    /// since they were sealed are not.
+    pub fn take_sealed(&mut self) -> impl Iterator<Item = BinId> {
+        std::mem::take(&mut self.sealed).into_iter()
+    }
+
+    /// Starts draining an open or sealed bin, so that items can only be removed from it and its
+    /// reservations can no longer be fulfilled. Returns `false` if the bin does not exist or is
+    /// already draining.
+    pub fn drain(&mut self, id: BinId) -> bool {
+        self.transition(
+            id,
+            &[BinState::Open, BinState::Sealed],
+            BinState::Draining,
+            Event::Draining(id),
+        )
+    }
+
+    /// Reopens a sealed or draining bin, so that it behaves normally again. Returns `false` if the
+    /// bin does not exist or is already open.
+    pub fn reopen(&mut self, id: BinId) -> bool {
+        self.sealed.retain(|&sealed| sealed != id);
+        self.transition(
+            id,
+            &[BinState::Sealed, BinState::Draining],
+            BinState::Open,
+            Event::Reopened(id),
+        )
+    }
+
+    /// Removes all bins that are empty and hold no reservations, returning their ids.
+    ///
+    /// The remaining bins keep their ids and order, but their indices change.
+    pub fn compact(&mut self) -> Vec<BinId> {
+        let mut removed = vec![];
+        self.slots.retain(|slot| {
+            let empty = slot.used == 0 && slot.reserved == 0;
+            if empty {
+                removed.push(slot.id);
+            }
+            !empty
+        });
+        self.rooms = self.slots.iter().map(Room::of).collect();
+        self.index = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(idx, slot)| (slot.id, idx))
+            .collect();
+        self.rebuild_emptiest();
+        for id in &removed {
+            self.bands.remove(id);
+        }
+        self.sealed.retain(|id| !removed.contains(id));
+        self.watch_total();
+        removed
+    }
+
+    /// Explains where the strategy would pack an item and why, returning the verdict on each bin
+    /// without changing any of them.
+    ///
+    /// Bins that are not open are rejected as `"sealed"` or `"draining"`, before the strategy
+    /// gets to explain its verdicts on the rest.
+    pub fn explain(&self, item: &impl Item) -> Vec<(BinId, Verdict)> {
+        let verdicts = self.strategy.explain(&self.slots, item);
+        let verdicts = relabel(verdicts, |i| match self.slots[i].state {
+            BinState::Open => None,
+            BinState::Sealed => Some("sealed"),
+            BinState::Draining => Some("draining"),
+        });
+        self.slots
+            .iter()
+            .map(|slot| slot.id)
+            .zip(verdicts)
+            .collect()
+    }
+
+    /// Changes the capacity of a bin, such as when a host is resized, returning by how much its
+    /// load now exceeds the new capacity, or `None` if the bin does not exist.
+    ///
+    /// Any overflow is left in place, and can be moved elsewhere with [`Packer::relocate`].
+    pub fn resize(&mut self, id: BinId, capacity: usize) -> Option<usize> {
+        let idx = self.index_of(id)?;
+        self.slots[idx].capacity = capacity;
+        self.refresh(idx);
+        Some(self.slots[idx].overflow())
+    }
+
+    /// Moves a bin into the given group, or out of any group, which new bins take from the
+    /// default `B`. Returns `false` if the bin does not exist.
+    pub fn set_group(&mut self, id: BinId, group: Option<&str>) -> bool {
+        let Some(idx) = self.index_of(id) else {
+            return false;
+        };
+        self.slots[idx].group = group.map(String::from);
+        true
+    }
+
+    /// Proposes which of the given items, which are in the bin with the given id, to move out of
+    /// it to end its overflow, and where to move them. Returns `None` if the bin does not exist.
+    ///
+    /// The largest items are evicted first, so as few items as possible move. The returned
+    /// proposal places the evicted items, in the order of `evict`, in other bins. After
+    /// committing it, remove the evicted items from the bin with [`Packer::remove`].
+    pub fn relocate(&self, id: BinId, items: &[impl Item]) -> Option<Relocation> {
+        let idx = self.index_of(id)?;
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| std::cmp::Reverse(items[i].size()));
+        let mut overflow = self.slots[idx].overflow();
+        let mut evict = vec![];
+        for i in order {
+            if overflow == 0 {
+                break;
+            }
+            overflow = overflow.saturating_sub(items[i].size());
+            evict.push(i);
+        }
+        let mut slots = self.slots.clone();
+        slots[idx].state = BinState::Draining;
+        let sizes = evict.iter().map(|&i| items[i].size()).collect::<Vec<_>>();
+        Some(Relocation {
+            proposal: self.propose_into(slots, &sizes),
+            evict,
+        })
+    }
+
+    /// Proposes where to pack a batch of items without changing any bins.
+    ///
+    /// The proposal places items exactly as packing them one after another would. Individual
+    /// placements can then be vetoed before committing the rest with [`Packer::commit`].
+    pub fn propose(&self, items: &[impl Item]) -> Proposal {
+        self.propose_into(self.slots.clone(), items)
+    }
+
+    /// Proposes where to pack items into the given copy of the bins.
+    fn propose_into(&self, mut slots: Vec<Slot<B>>, items: &[impl Item]) -> Proposal {
+        let placements = items
+            .iter()
+            .map(|item| {
+                let idx = self.strategy.next_idx(&slots, item).unwrap_or_else(|| {
+                    slots.push(Slot::default());
+                    slots.len() - 1
+                });
+                slots[idx].used += item.size();
+                let target = match self.slots.get(idx) {
+                    Some(slot) => Target::Bin(slot.id),
+                    None => Target::New(idx - self.slots.len()),
+                };
+                Some((target, item.size()))
+            })
+            .collect();
+        Proposal { placements }
+    }
+
+    /// Commits all placements of a proposal that were not vetoed, returning the bin each item was
+    /// packed into, or `None` for vetoed items.
+    ///
+    /// New bins are only opened if any item is left to be packed into them. If the bins have
+    /// changed since the proposal was made so that the placements no longer fit, nothing is
+    /// committed and `None` is returned.
+    pub fn commit(&mut self, proposal: Proposal) -> Option<Vec<Option<BinId>>> {
+        let mut added = HashMap::<Target, (usize, usize)>::new();
+        for &(target, size) in proposal.placements.iter().flatten() {
+            let (total, largest) = added.entry(target).or_default();
+            *total += size;
+            *largest = (*largest).max(size);
+        }
+        for (&target, &(total, largest)) in &added {
+            let slot = match target {
+                Target::Bin(id) => self.bin(id)?.clone(),
+                Target::New(_) => Slot::default(),
+            };
+            if !slot.fits(&largest) || !slot.has_room(total) {
+                return None;
+            }
+        }
+
+        let mut opened = added
+            .keys()
+            .filter_map(|&target| match target {
+                Target::New(k) => Some(k),
+                Target::Bin(_) => None,
+            })
+            .collect::<Vec<_>>();
+        opened.sort_unstable();
+        let opened = opened
+            .into_iter()
+            .map(|k| {
+                let idx = self.open();
+                (k, self.slots[idx].id)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let committed = proposal
+            .placements
+            .into_iter()
+            .map(|placement| {
+                let (target, size) = placement?;
+                let id = match target {
+                    Target::Bin(id) => id,
+                    Target::New(k) => opened[&k],
+                };
+                let idx = self.index[&id];
+                self.slots[idx].used += size;
+                self.refresh(idx);
+                Some(id)
+            })
+            .collect();
+        Some(committed)
+    }
+
+    /// Reserves space in a bin for a future item, which strategies treat as used until the
+    /// reservation is fulfilled or released.
+    ///
+    /// Returns `None` if the bin does not exist or does not have enough available capacity.
+    pub fn reserve(&mut self, bin: BinId, size: usize) -> Option<ReservationId> {
+        let idx = self.index_of(bin)?;
+        let slot = &mut self.slots[idx];
+        if slot.state != BinState::Open || !slot.fits(&size) {
+            return None;
+        }
+        slot.reserved += size;
+        self.refresh(idx);
+        let id = ReservationId(self.next_reservation);
+        self.next_reservation += 1;
+        self.reservations.insert(id, Reservation { bin, size });
+        Some(id)
+    }
+
+    /// Packs an item into the space held by a reservation, returning its bin.
+    ///
+    /// Any reserved space the item does not need becomes available again. The item may also be
+    /// larger than the reservation if the bin has enough capacity available to make up the
+    /// difference. Returns `None`, keeping the reservation, if it does not fit either way or the
+    /// reservation does not exist.
+    pub fn fulfill(&mut self, id: ReservationId, item: &impl Item) -> Option<BinId> {
+        let reservation = self.reservations.get(&id)?;
+        let idx = self.index[&reservation.bin];
+        let slot = &mut self.slots[idx];
+        let extra = item.size().saturating_sub(reservation.size);
+        if slot.state == BinState::Draining
+            || item.size() > slot.max_item_size
+            || !slot.has_room(extra)
+        {
+            return None;
+        }
+        slot.reserved -= reservation.size;
+        slot.used += item.size();
+        self.refresh(idx);
+        Some(self.reservations.remove(&id)?.bin)
+    }
+
+    /// Releases a reservation, making its space available again. Returns `false` if the
+    /// reservation does not exist.
+    pub fn release(&mut self, id: ReservationId) -> bool {
+        let Some(reservation) = self.reservations.remove(&id) else {
+            return false;
+        };
+        let idx = self.index[&reservation.bin];
+        self.slots[idx].reserved -= reservation.size;
+        self.refresh(idx);
+        true
+    }
+
+    /// Returns the fraction of the total capacity of all bins that is taken up by items and
+    /// reservations.
+    pub fn utilization(&self) -> f64 {
+        if self.slots.is_empty() {
+            return 0.0;
+        }
+        let load = self.slots.iter().map(Bin::load).sum::<usize>();
+        let capacity = self.slots.iter().map(|slot| slot.capacity).sum::<usize>();
+        load as f64 / capacity.max(1) as f64
+    }
+
+    /// Returns how many more items of the given size, such as the mean size of recent arrivals,
+    /// fit into the open bins before a new bin has to be opened, so that producers can be
+    /// throttled or capacity added ahead of time.
+    ///
+    /// This counts where the items fit rather than where the strategy would put them, so it
+    /// assumes a strategy that uses every bin an item fits into. Items of size zero always fit,
+    /// which is reported as `usize::MAX` if any bin is open.
+    pub fn absorbs(&self, size: usize) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.state == BinState::Open && size <= slot.max_item_size)
+            .map(|slot| {
+                let room = (slot.capacity + B::tolerance()).saturating_sub(slot.load());
+                room.checked_div(size).unwrap_or(usize::MAX)
+            })
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Checks the bins against the given watermarks, returning a recommendation to consolidate
+    /// them if utilization is too low or there are too many bins.
+    ///
+    /// The recommended bins to drain are the emptiest ones whose loads the remaining open bins
+    /// have room for in total, until the watermarks are met or no more bins can be drained. Bins
+    /// holding reservations or already draining are left alone. As the packer does not know the
+    /// items, the plan relies on their loads only, so moving the items may still need a new bin
+    /// if they do not split up well.
+    pub fn check(&self, watermarks: &Watermarks) -> Option<Recommendation> {
+        let utilization = self.utilization();
+        let trigger = if watermarks
+            .max_bins
+            .is_some_and(|max| self.slots.len() > max)
+        {
+            Trigger::TooManyBins(self.slots.len())
+        } else if utilization < watermarks.low_utilization {
+            Trigger::LowUtilization(utilization)
+        } else {
+            return None;
+        };
+
+        let load = self.slots.iter().map(Bin::load).sum::<usize>();
+        let mut capacity = self.slots.iter().map(|slot| slot.capacity).sum::<usize>();
+        let met = |bins: usize, capacity: usize| {
+            watermarks.max_bins.is_none_or(|max| bins <= max)
+                && load as f64 >= watermarks.low_utilization * capacity as f64
+        };
+        let mut candidates = (0..self.slots.len())
+            .filter(|&i| self.slots[i].state != BinState::Draining && self.slots[i].reserved == 0)
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|&i| self.slots[i].used);
+        let mut drained = vec![false; self.slots.len()];
+        let mut moved = 0;
+        let mut drain = vec![];
+        for i in candidates {
+            if met(self.slots.len() - drain.len(), capacity) {
+                break;
+            }
+            drained[i] = true;
+            let room = (0..self.slots.len())
+                .filter(|&j| !drained[j] && self.slots[j].state == BinState::Open)
+                .map(|j| self.slots[j].available())
+                .sum::<usize>();
+            if moved + self.slots[i].used <= room {
+                moved += self.slots[i].used;
+                capacity -= self.slots[i].capacity;
+                drain.push(self.slots[i].id);
+            } else {
+                drained[i] = false;
+            }
+        }
+        Some(Recommendation { trigger, drain })
+    }
+
+    /// Opens a new bin with a fresh id, returning its index.
+    fn open(&mut self) -> usize {
+        let id = BinId(self.next_bin);
+        self.next_bin += 1;
+        self.slots.push(Slot {
+            id,
+            ..Slot::default()
+        });
+        self.rooms.push(Room::default());
+        self.index.insert(id, self.slots.len() - 1);
+        self.notify(Event::Opened(id));
+        self.refresh(self.slots.len() - 1);
+        self.slots.len() - 1
+    }
+
+    /// Moves a bin from one of the given states to another, notifying observers.
+    fn transition(&mut self, id: BinId, from: &[BinState], to: BinState, event: Event) -> bool {
+        let Some(idx) = self.index_of(id) else {
+            return false;
+        };
+        if !from.contains(&self.slots[idx].state) {
+            return false;
+        }
+        self.slots[idx].state = to;
+        self.refresh(idx);
+        self.notify(event);
+        true
+    }
+
+    /// Brings the compact view of a slot, and its entry in the heap of the emptiest bins if the
+    /// strategy uses it, up to date after the slot changed.
+    fn refresh(&mut self, idx: usize) {
+        let slot = &self.slots[idx];
+        self.rooms[idx] = Room::of(slot);
+        if self.strategy.emptiest_first() && slot.state == BinState::Open {
+            self.emptiest.push((slot.available(), Reverse(slot.id)));
+            if self.emptiest.len() > 2 * self.slots.len() + 16 {
+                self.rebuild_emptiest();
+            }
+        }
+        self.watch_bin(idx);
+        self.watch_total();
+    }
+
+    /// Notifies observers of the watermarks the utilization of a slot crossed since it last
+    /// changed, starting from its band when first seen.
+    fn watch_bin(&mut self, idx: usize) {
+        let Some(levels) = self.levels else {
+            return;
+        };
+        let slot = &self.slots[idx];
+        let (id, band) = (slot.id, levels.band(slot.utilization()));
+        if let Some(before) = self.bands.insert(id, band) {
+            for (mark, rising) in crossings(before, band) {
+                self.notify(Event::Crossed {
+                    bin: Some(id),
+                    mark,
+                    rising,
+                });
+            }
+        }
+    }
+
+    /// Notifies observers of the watermarks the utilization of all bins crossed since it was last
+    /// checked.
+    fn watch_total(&mut self) {
+        let Some(levels) = self.levels else {
+            return;
+        };
+        let band = levels.band(self.utilization());
+        if let Some(before) = self.band.replace(band) {
+            for (mark, rising) in crossings(before, band) {
+                self.notify(Event::Crossed {
+                    bin: None,
+                    mark,
+                    rising,
+                });
+            }
+        }
+    }
+
+    /// Rebuilds the heap of the emptiest bins without stale entries.
+    fn rebuild_emptiest(&mut self) {
+        self.emptiest.clear();
+        if self.strategy.emptiest_first() {
+            let open = self
+                .slots
+                .iter()
+                .filter(|slot| slot.state == BinState::Open);
+            self.emptiest = open
+                .map(|slot| (slot.available(), Reverse(slot.id)))
+                .collect();
+        }
+    }
+
+    /// Returns the index of the first of the bins with the most available capacity that the item
+    /// fits into, using the heap of the emptiest bins.
+    fn emptiest(&mut self, item: &impl Item) -> Option<usize> {
+        let mut skipped = vec![];
+        let found = loop {
+            let Some(entry @ (available, Reverse(id))) = self.emptiest.pop() else {
+                break None;
+            };
+            let Some(idx) = self.index_of(id) else {
+                continue;
+            };
+            let slot = &self.slots[idx];
+            if slot.state != BinState::Open || slot.available() != available {
+                continue;
+            }
+            skipped.push(entry);
+            if slot.fits(item) {
+                break Some(idx);
+            }
+            // Bins further down have no more room than this one unless it is overfull, so they are
+            // only worth looking at if it rejected the item for its maximum item size.
+            if !slot.has_room(item.size()) && slot.load() <= slot.capacity {
+                break None;
+            }
+        };
+        self.emptiest.extend(skipped);
+        found
+    }
+
+    fn notify(&mut self, event: Event) {
+        for observer in &mut self.observers {
+            observer(event);
+        }
+    }
+}
+
+/// Something that happened to the bins of a [`Packer`], see [`Packer::observe`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    /// A new bin was opened.
+    Opened(BinId),
+    /// A bin was sealed.
+    Sealed(BinId),
+    /// A bin started draining.
+    Draining(BinId),
+    /// A sealed or draining bin was reopened.
+    Reopened(BinId),
+    /// The utilization of a bin, or of all bins if `None`, crossed a watermark set with
+    /// [`Packer::watch`].
+    Crossed {
+        /// The bin, or `None` for all bins together.
+        bin: Option<BinId>,
+        /// The watermark crossed.
+        mark: Mark,
+        /// Whether utilization rose above the watermark rather than fell below it.
+        rising: bool,
+    },
+}
+
+/// Utilization watermarks, between 0 and 1, at which a [`Packer`] notifies its observers, see
+/// [`Packer::watch`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Levels {
+    /// The utilization below which bins are underused.
+    pub low: f64,
+    /// The utilization above which bins are nearly full.
+    pub high: f64,
+}
+
+impl Levels {
+    fn band(&self, utilization: f64) -> Band {
+        if utilization < self.low {
+            Band::Low
+        } else if utilization > self.high {
+            Band::High
+        } else {
+            Band::Normal
+        }
+    }
+}
+
+/// A watermark of [`Levels`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mark {
+    /// [`Levels::low`].
+    Low,
+    /// [`Levels::high`].
+    High,
+}
+
+/// Where utilization is relative to the [`Levels`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Band {
+    Low,
+    Normal,
+    High,
+}
+
+/// Returns the watermarks crossed going from one band to another, in the order they are crossed,
+/// and whether each was crossed rising.
+fn crossings(from: Band, to: Band) -> Vec<(Mark, bool)> {
+    let mut crossed = vec![];
+    if from == Band::Low && to > Band::Low {
+        crossed.push((Mark::Low, true));
+    }
+    if from == Band::High && to < Band::High {
+        crossed.push((Mark::High, false));
+    }
+    if to == Band::High && from < Band::High {
+        crossed.push((Mark::High, true));
+    }
+    if to == Band::Low && from > Band::Low {
+        crossed.push((Mark::Low, false));
+    }
+    crossed
+}
+
+/// The lifecycle state of a bin of a [`Packer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BinState {
+    /// Items can be packed into the bin and removed from it.
+    #[default]
+    Open,
+    /// Strategies no longer pack items into the bin, but its reservations can be fulfilled.
+    Sealed,
+    /// Items can only be removed from the bin.
+    Draining,
+}
+
+/// Identifies a bin of a [`Packer`].
+///
+/// Ids are assigned in the order bins are opened and never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BinId(usize);
+
+/// Where a [`Proposal`] would pack an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// An existing bin.
+    Bin(BinId),
+    /// The n-th new bin the proposal would open, counting from zero.
+    New(usize),
+}
+
+/// Placements for a batch of items proposed by [`Packer::propose`].
+#[derive(Clone, Debug)]
+pub struct Proposal {
+    placements: Vec<Option<(Target, usize)>>,
+}
+
+impl Proposal {
+    /// Returns where each item would be packed, in item order, or `None` for vetoed items.
+    pub fn placements(&self) -> impl Iterator<Item = Option<Target>> + '_ {
+        self.placements.iter().map(|p| p.map(|(target, _)| target))
+    }
+
+    /// Vetoes the placement of the item at the given index, so it is not packed when committing.
+    pub fn veto(&mut self, item: usize) {
+        self.placements[item] = None;
+    }
+}
+
+/// What removing an item from a bin would change, see [`Packer::removal_impact`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemovalImpact {
+    /// How much available capacity the removal would free up, which may be less than the size of
+    /// the item if the bin is overfull.
+    pub freed: usize,
+    /// The available capacity of the bin after the removal.
+    pub available: usize,
+    /// The indices of the waiting items that would fit into the bin after the removal only.
+    pub fits: Vec<usize>,
+}
+
+/// How to end the overflow of a resized bin, see [`Packer::relocate`].
+#[derive(Clone, Debug)]
+pub struct Relocation {
+    /// The indices of the items to move out of the bin, largest first.
+    pub evict: Vec<usize>,
+    /// Where to move the evicted items.
+    pub proposal: Proposal,
+}
+
+/// Thresholds for [`Packer::check`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Watermarks {
+    /// The utilization, between 0 and 1, below which bins should be consolidated.
+    pub low_utilization: f64,
+    /// The number of bins above which bins should be consolidated, if any.
+    pub max_bins: Option<usize>,
+}
+
+/// When [`Packer::pack`] seals the bin it packed an item into, see [`Packer::close_below`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Closing {
+    /// Seals bins with less than the given capacity available.
+    Fixed(usize),
+    /// Seals bins with less capacity available than an exponentially weighted moving average of
+    /// the sizes of the items packed, where `weight`, between 0 and 1, is the weight of the latest
+    /// size. The threshold then follows items as they shrink or grow over time, so bins are not
+    /// closed early once items that would still fit get smaller.
+    Adaptive {
+        /// The weight of the latest size in the average.
+        weight: f64,
+    },
+}
+
+/// Which watermark a [`Recommendation`] was made for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    /// The utilization of the bins, which is below the low watermark.
+    LowUtilization(f64),
+    /// The number of bins, which is above the maximum.
+    TooManyBins(usize),
+}
+
+/// A recommendation to consolidate bins, returned by [`Packer::check`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recommendation {
+    /// Why consolidating is recommended.
+    pub trigger: Trigger,
+    /// The bins to drain, emptiest first.
+    pub drain: Vec<BinId>,
+}
+
+/// Identifies a reservation made with [`Packer::reserve`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReservationId(usize);
+
+struct Reservation {
+    bin: BinId,
+    size: usize,
+}
+
+/// The packer's view of a bin.
+///
+/// Strategies see the space held by reservations as unavailable, and only pack items into bins
+/// that are [`BinState::Open`].
+pub struct Slot<B> {
+    id: BinId,
+    used: usize,
+    reserved: usize,
+    state: BinState,
+    capacity: usize,
+    max_item_size: usize,
+    group: Option<String>,
+    bin: PhantomData<B>,
+}
+
+impl<B> Slot<B> {
+    /// Returns the id of the bin.
+    pub fn id(&self) -> BinId {
+        self.id
+    }
+
+    /// Returns the total size of the items packed into the bin.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Returns the space held by reservations.
+    pub fn reserved(&self) -> usize {
+        self.reserved
+    }
+
+    /// Returns the lifecycle state of the bin.
+    pub fn state(&self) -> BinState {
+        self.state
+    }
+
+    /// Returns the capacity of the bin, which is the capacity of `B` unless the bin has been
+    /// resized with [`Packer::resize`].
+    pub fn limit(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<B: Bin> Slot<B> {
+    /// Returns whether the bin can take on the given size on top of its load, allowing for the
+    /// tolerance of `B`.
+    fn has_room(&self, size: usize) -> bool {
+        self.load() + size <= self.capacity + B::tolerance()
+    }
+
+    /// Returns the fraction of the capacity of the bin taken up by items and reservations.
+    fn utilization(&self) -> f64 {
+        self.load() as f64 / self.capacity.max(1) as f64
+    }
+
+    /// Returns by how much the load of the bin exceeds its capacity plus the tolerance of `B`.
+    fn overflow(&self) -> usize {
+        self.load().saturating_sub(self.capacity + B::tolerance())
+    }
+}
+
+impl<B> Clone for Slot<B> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            used: self.used,
+            reserved: self.reserved,
+            state: self.state,
+            capacity: self.capacity,
+            max_item_size: self.max_item_size,
+            group: self.group.clone(),
+            bin: PhantomData,
+        }
+    }
+}
+
+/// Creates a bin that is not known to any packer.
+impl<B: Bin> Default for Slot<B> {
+    fn default() -> Self {
+        Self {
+            id: BinId(usize::MAX),
+            used: 0,
+            reserved: 0,
+            state: BinState::Open,
+            capacity: B::capacity(),
+            max_item_size: B::default().max_item_size(),
+            group: B::default().group().map(String::from),
+            bin: PhantomData,
+        }
+    }
+}
+
+impl<B: Bin> Bin for Slot<B> {
+    fn capacity() -> usize {
+        B::capacity()
+    }
+    fn available(&self) -> usize {
+        self.capacity.saturating_sub(self.load())
+    }
+    fn pack(&mut self, item: impl Item) {
+        self.used += item.size();
+    }
+    fn load(&self) -> usize {
+        self.used + self.reserved
+    }
+    fn tolerance() -> usize {
+        B::tolerance()
+    }
+    fn max_item_size(&self) -> usize {
+        self.max_item_size
+    }
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+    fn fits(&self, item: &impl Item) -> bool {
+        self.state == BinState::Open
+            && item.size() <= self.max_item_size
+            && self.has_room(item.size())
+    }
+}
+
+/// A compact view of a [`Slot`] for strategies that only look at availability, see
+/// [`Strategy::availability_only`].
+struct Room<B> {
+    available: usize,
+    /// One more than the size of the largest item the slot accepts, or zero if it accepts none.
+    room: usize,
+    bin: PhantomData<B>,
+}
+
+impl<B: Bin> Room<B> {
+    fn of(slot: &Slot<B>) -> Self {
+        let room = match slot.state {
+            BinState::Open => (slot.capacity + B::tolerance())
+                .checked_sub(slot.load())
+                .map_or(0, |room| room.min(slot.max_item_size).saturating_add(1)),
+            BinState::Sealed | BinState::Draining => 0,
+        };
+        Self {
+            available: slot.available(),
+            room,
+            bin: PhantomData,
+        }
+    }
+}
+
+impl<B: Bin> Default for Room<B> {
+    fn default() -> Self {
+        Self::of(&Slot::default())
+    }
+}
+
+impl<B: Bin> Bin for Room<B> {
+    fn capacity() -> usize {
+        B::capacity()
+    }
+    fn available(&self) -> usize {
+        self.available
+    }
+    fn pack(&mut self, item: impl Item) {
+        self.available = self.available.saturating_sub(item.size());
+        self.room = self.room.saturating_sub(item.size());
+    }
+    fn tolerance() -> usize {
+        B::tolerance()
+    }
+    fn fits(&self, item: &impl Item) -> bool {
+        item.size() < self.room
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::online::FirstFit;
+
+    #[derive(Default)]
+    struct BinImpl;
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity()
+        }
+        fn pack(&mut self, _item: impl Item) {}
+    }
+
+    #[test]
+    fn pack_opens_bins_as_needed() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let a = packer.pack(&6);
+        let b = packer.pack(&6);
+        assert_ne!(a, b);
+        assert_eq!(packer.pack(&4), a);
+        assert_eq!(packer.bin(a).unwrap().used(), 10);
+    }
+
+    #[test]
+    fn stateful_strategies_are_asked_once_per_item() {
+        // Under the `cross-check` feature, asking the strategy a second time would count each item
+        // twice against the limit.
+        let strategy = crate::constraints::Constraints::new()
+            .max_items(2)
+            .apply(FirstFit);
+        let mut packer = Packer::<BinImpl, _>::new(strategy);
+        let a = packer.pack(&1);
+        assert_eq!(packer.pack(&1), a);
+        let b = packer.pack(&1);
+        assert_ne!(b, a);
+        assert_eq!(packer.pack(&1), b);
+        assert_eq!(packer.bins().len(), 2);
+    }
+
+    #[test]
+    fn ids_survive_compaction() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let a = packer.pack(&6);
+        let b = packer.pack(&6);
+        let c = packer.pack(&6);
+        packer.slots[1].used = 0;
+        assert_eq!(packer.compact(), vec![b]);
+        assert_eq!(packer.index_of(b), None);
+        assert_eq!(packer.index_of(c), Some(1));
+        assert_eq!(packer.bin(a).unwrap().used(), 6);
+        assert_ne!(packer.pack(&6), b);
+    }
+
+    #[test]
+    fn rooms_track_slots() {
+        let mut packer = Packer::<BinImpl, _>::new(online::BestFit);
+        let in_sync = |packer: &Packer<BinImpl, online::BestFit>| {
+            packer.slots.iter().zip(&packer.rooms).all(|(slot, room)| {
+                let fresh = Room::of(slot);
+                (room.available, room.room) == (fresh.available, fresh.room)
+            })
+        };
+        let a = packer.pack(&6);
+        let b = packer.pack(&7);
+        let reservation = packer.reserve(a, 3).unwrap();
+        assert!(in_sync(&packer));
+        assert_eq!(packer.pack(&3), b);
+        packer.release(reservation);
+        packer.seal(a);
+        packer.resize(b, 12);
+        assert!(in_sync(&packer));
+        assert_eq!(packer.pack(&2), b);
+        packer.remove(b, &7);
+        assert!(packer.rooms[0].room == 0 && in_sync(&packer));
+    }
+
+    #[test]
+    fn emptiest_bins_come_from_the_heap() {
+        let mut packer = Packer::<BinImpl, _>::new(online::WorstFit);
+        let mut packed = vec![];
+        for (step, size) in [4, 7, 2, 9, 3, 3, 5, 1, 6, 2, 8, 4].into_iter().enumerate() {
+            let expected = online::WorstFit.next_idx(&packer.slots, &size);
+            let id = packer.pack(&size);
+            assert_eq!(
+                expected.map(|idx| packer.slots[idx].id),
+                expected.and(Some(id))
+            );
+            packed.push((id, size));
+            if step % 3 == 2 {
+                let (id, size) = packed.remove(0);
+                packer.remove(id, &size);
+            }
+            if step == 5 {
+                packer.seal(packer.slots[0].id);
+            }
+        }
+        assert!(packer.emptiest.len() <= 2 * packer.slots.len() + 16);
+    }
+
+    #[test]
+    fn reserved_space_is_unavailable() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&2);
+        let id = packer.reserve(bin, 5).unwrap();
+        assert_eq!(packer.reserve(bin, 4), None);
+        assert_ne!(packer.pack(&4), bin);
+
+        assert_eq!(packer.fulfill(id, &9), None);
+        assert_eq!(packer.fulfill(id, &6), Some(bin));
+        assert_eq!(packer.bin(bin).unwrap().available(), 2);
+        assert!(!packer.release(id));
+    }
+
+    #[test]
+    fn released_space_is_available_again() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&2);
+        let id = packer.reserve(bin, 8).unwrap();
+        assert_ne!(packer.pack(&3), bin);
+        assert!(packer.release(id));
+        assert_eq!(packer.pack(&3), bin);
+    }
+
+    #[test]
+    fn propose_does_not_pack() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&4);
+        let proposal = packer.propose(&[6, 7, 3]);
+        assert_eq!(
+            proposal.placements().collect::<Vec<_>>(),
+            vec![
+                Some(Target::Bin(bin)),
+                Some(Target::New(0)),
+                Some(Target::New(0))
+            ]
+        );
+        assert_eq!(packer.bins().len(), 1);
+        assert_eq!(packer.bin(bin).unwrap().used(), 4);
+    }
+
+    #[test]
+    fn commit_packs_approved_placements() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&4);
+        let mut proposal = packer.propose(&[6, 7, 3]);
+        proposal.veto(1);
+        let committed = packer.commit(proposal).unwrap();
+        assert_eq!(committed[0], Some(bin));
+        assert_eq!(committed[1], None);
+        assert_eq!(packer.bin(bin).unwrap().used(), 10);
+        assert_eq!(packer.bin(committed[2].unwrap()).unwrap().used(), 3);
+    }
+
+    #[test]
+    fn commit_fails_on_stale_proposal() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&4);
+        let proposal = packer.propose(&[6]);
+        packer.pack(&2);
+        assert_eq!(packer.commit(proposal), None);
+        assert_eq!(packer.bin(bin).unwrap().used(), 6);
+    }
+
+    #[test]
+    fn bin_states_restrict_packing() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let a = packer.pack(&4);
+        let id = packer.reserve(a, 2).unwrap();
+        assert!(packer.seal(a));
+        assert_eq!(packer.bin(a).unwrap().available(), 4);
+        assert_ne!(packer.pack(&1), a);
+        assert_eq!(packer.reserve(a, 1), None);
+        assert_eq!(packer.fulfill(id, &3), Some(a));
+
+        assert!(packer.reopen(a));
+        let id = packer.reserve(a, 1).unwrap();
+        assert!(packer.drain(a));
+        assert_eq!(packer.bin(a).unwrap().state(), BinState::Draining);
+        assert_eq!(packer.fulfill(id, &1), None);
+        assert!(packer.remove(a, &3));
+        assert!(!packer.remove(a, &5));
+        assert_eq!(packer.bin(a).unwrap().used(), 4);
+        assert!(!packer.drain(a));
+    }
+
+    #[test]
+    fn check_recommends_draining_emptiest_bins() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        packer.pack(&7);
+        let b = packer.pack(&6);
+        let c = packer.pack(&5);
+        packer.reserve(b, 1).unwrap();
+
+        let mut watermarks = Watermarks {
+            low_utilization: 0.5,
+            max_bins: None,
+        };
+        assert_eq!(packer.check(&watermarks), None);
+        watermarks.low_utilization = 0.8;
+        let recommendation = packer.check(&watermarks).unwrap();
+        assert!(matches!(recommendation.trigger, Trigger::LowUtilization(u) if u < 0.65));
+        assert_eq!(recommendation.drain, vec![c]);
+
+        watermarks.max_bins = Some(1);
+        let recommendation = packer.check(&watermarks).unwrap();
+        assert_eq!(recommendation.trigger, Trigger::TooManyBins(3));
+        assert_eq!(recommendation.drain, vec![c]);
+    }
+
+    #[test]
+    fn explain_gives_reasons_for_each_bin() {
+        struct Owned(usize);
+        impl Item for Owned {
+            fn size(&self) -> usize {
+                self.0
+            }
+            fn owner(&self) -> Option<usize> {
+                Some(0)
+            }
+        }
+
+        let mut packer = Packer::<BinImpl, _>::new(crate::online::Quotas::new(FirstFit, 6));
+        let a = packer.pack(&Owned(4));
+        let b = packer.pack(&8);
+        let c = packer.pack(&Owned(5));
+        assert!(packer.seal(c));
+        assert_eq!(
+            packer.explain(&Owned(3)),
+            vec![
+                (a, Verdict::Rejected("quota")),
+                (b, Verdict::Rejected("capacity")),
+                (c, Verdict::Rejected("sealed")),
+            ]
+        );
+    }
+
+    #[test]
+    fn removal_impact_does_not_remove() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let bin = packer.pack(&7);
+        packer.pack(&2);
+        let impact = packer.removal_impact(bin, &4, &[1, 3, 5, 8]).unwrap();
+        assert_eq!(
+            impact,
+            RemovalImpact {
+                freed: 4,
+                available: 5,
+                fits: vec![1, 2],
+            }
+        );
+        assert_eq!(packer.bin(bin).unwrap().used(), 9);
+        assert_eq!(packer.removal_impact(bin, &10, &[1]), None);
+    }
+
+    #[test]
+    fn shrinking_bins_relocates_overflow() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let a = packer.pack(&4);
+        packer.pack(&3);
+        packer.pack(&2);
+        let b = packer.pack(&5);
+        assert_eq!(packer.resize(a, 6), Some(3));
+        assert_eq!(packer.bin(a).unwrap().available(), 0);
+        assert_ne!(packer.pack(&1), a);
+
+        let relocation = packer.relocate(a, &[4, 3, 2]).unwrap();
+        assert_eq!(relocation.evict, vec![0]);
+        assert_eq!(
+            relocation.proposal.placements().collect::<Vec<_>>(),
+            vec![Some(Target::Bin(b))]
+        );
+        packer.commit(relocation.proposal).unwrap();
+        assert!(packer.remove(a, &4));
+        assert_eq!(packer.resize(a, 6), Some(0));
+        assert_eq!(packer.bin(b).unwrap().used(), 10);
+    }
+
+    #[test]
+    fn observers_see_opened_and_sealed_bins() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        let events = Rc::new(RefCell::new(vec![]));
+        let seen = events.clone();
+        packer.observe(move |event| seen.borrow_mut().push(event));
+
+        let a = packer.pack(&2);
+        assert!(packer.seal(a));
+        assert!(!packer.seal(a));
+        let b = packer.pack(&2);
+        assert_ne!(a, b);
+        assert_eq!(
+            *events.borrow(),
+            vec![Event::Opened(a), Event::Sealed(a), Event::Opened(b)]
+        );
+    }
+
+    #[test]
+    fn closing_threshold_follows_item_sizes() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        packer.close_below(Closing::Fixed(3));
+        let a = packer.pack(&8);
+        assert_eq!(packer.bin(a).unwrap().state(), BinState::Sealed);
+        assert_ne!(packer.pack(&2), a);
+
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        packer.close_below(Closing::Adaptive { weight: 0.5 });
+        assert_eq!(packer.close_threshold(), None);
+        let a = packer.pack(&4);
+        assert_eq!(packer.close_threshold(), Some(4));
+        // Smaller items lower the threshold, so the bin stays open for them where a fixed
+        // threshold of 4 would have closed it.
+        for _ in 0..4 {
+            assert_eq!(packer.pack(&1), a);
+        }
+        assert_eq!(packer.close_threshold(), Some(1));
+        assert_eq!(packer.bin(a).unwrap().state(), BinState::Open);
+        assert_eq!(packer.pack(&2), a);
+        assert_eq!(packer.bin(a).unwrap().state(), BinState::Sealed);
+        assert_eq!(packer.bin(a).unwrap().used(), 10);
+    }
+
+    #[test]
+    fn absorbs_counts_items_open_bins_fit() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        assert_eq!(packer.absorbs(3), 0);
+        let a = packer.pack(&4);
+        let b = packer.pack(&7);
+        assert_eq!(packer.absorbs(3), 3);
+        assert_eq!(packer.absorbs(7), 0);
+        packer.seal(b);
+        assert_eq!(packer.absorbs(3), 2);
+        assert_eq!(packer.absorbs(0), usize::MAX);
+        packer.seal(a);
+        assert_eq!(packer.absorbs(0), 0);
+    }
+
+    #[test]
+    fn observers_see_watermark_crossings() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        packer.watch(Levels {
+            low: 0.3,
+            high: 0.8,
+        });
+        let events = Rc::new(RefCell::new(vec![]));
+        let seen = events.clone();
+        packer.observe(move |event| seen.borrow_mut().push(event));
+        let crossed = |bin, mark, rising| Event::Crossed { bin, mark, rising };
+
+        let a = packer.pack(&9);
+        let b = packer.pack(&5);
+        packer.remove(a, &9);
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                Event::Opened(a),
+                crossed(Some(a), Mark::Low, true),
+                crossed(Some(a), Mark::High, true),
+                crossed(None, Mark::Low, true),
+                crossed(None, Mark::High, true),
+                Event::Opened(b),
+                crossed(None, Mark::High, false),
+                crossed(Some(b), Mark::Low, true),
+                crossed(Some(a), Mark::High, false),
+                crossed(Some(a), Mark::Low, false),
+                crossed(None, Mark::Low, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn sealed_bins_are_taken_in_order() {
+        let mut packer = Packer::<BinImpl, _>::new(FirstFit);
+        packer.close_below(Closing::Fixed(2));
+        let a = packer.pack(&5);
+        let b = packer.pack(&9);
+        let c = packer.pack(&6);
+        assert_eq!(packer.take_sealed().collect::<Vec<_>>(), [b]);
+        assert!(packer.seal(c));
+        assert!(packer.seal(a));
+        assert!(packer.reopen(c));
+        assert_eq!(packer.take_sealed().collect::<Vec<_>>(), [a]);
+        assert_eq!(packer.take_sealed().count(), 0);
+    }
+}
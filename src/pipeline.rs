@@ -0,0 +1,220 @@
+//! Placement pipelines, which combine filters and weighted scores into an online strategy.
+//!
+//! Rather than writing a new strategy for each placement policy, a [`Pipeline`] evaluates every
+//! bin for an item in two stages, like the Kubernetes scheduler: filters rule bins out as hard
+//! constraints, and scores rank the remaining ones as weighted soft preferences. The item goes
+//! into the bin with the highest total score, and [`Pipeline::decide`] reports how each bin fared.
+
+use crate::online::{capacity, Strategy, Verdict};
+use crate::*;
+
+/// A bin being considered for an item, as seen by the filters and scores of a [`Pipeline`].
+pub struct Candidate<'a> {
+    /// The index of the bin.
+    pub index: usize,
+    /// The capacity of the bin.
+    pub capacity: usize,
+    /// How much of the capacity of the bin is taken up, see [`Bin::load`].
+    pub load: usize,
+    /// The group of the bin, see [`Bin::group`].
+    pub group: Option<&'a str>,
+    /// The item to place.
+    pub item: &'a dyn Item,
+}
+
+impl Candidate<'_> {
+    /// Returns the fraction of the capacity of the bin that would be taken up after packing the
+    /// item.
+    pub fn fill(&self) -> f64 {
+        (self.load + self.item.size()) as f64 / self.capacity.max(1) as f64
+    }
+}
+
+/// Scores bins by how full they would be after packing the item, preferring fuller bins like best
+/// fit.
+pub fn fuller(candidate: &Candidate) -> f64 {
+    candidate.fill()
+}
+
+/// Scores bins by how empty they would be after packing the item, preferring emptier bins like
+/// worst fit.
+pub fn emptier(candidate: &Candidate) -> f64 {
+    1.0 - candidate.fill()
+}
+
+type FilterFn = Box<dyn Fn(&Candidate) -> bool>;
+type ScoreFn = Box<dyn Fn(&Candidate) -> f64>;
+
+/// An online strategy made up of named filters and weighted scores.
+///
+/// Bins the item does not fit into are always filtered out first. Among the bins that pass every
+/// filter, the item goes into the one with the highest sum of weighted scores, the first one on
+/// ties, or into a new bin if no bin passes.
+#[derive(Default)]
+pub struct Pipeline {
+    filters: Vec<(&'static str, FilterFn)>,
+    scores: Vec<(&'static str, f64, ScoreFn)>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline without any filters or scores, which packs like first fit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a filter, which rules out every bin for which it returns `false`. Filters run in the
+    /// order they were added.
+    pub fn filter(mut self, name: &'static str, f: impl Fn(&Candidate) -> bool + 'static) -> Self {
+        self.filters.push((name, Box::new(f)));
+        self
+    }
+
+    /// Adds a score, which counts towards the total score of a bin multiplied by its weight.
+    ///
+    /// Scores are easiest to weigh against each other if they range from 0 to 1.
+    pub fn score(
+        mut self,
+        name: &'static str,
+        weight: f64,
+        f: impl Fn(&Candidate) -> f64 + 'static,
+    ) -> Self {
+        self.scores.push((name, weight, Box::new(f)));
+        self
+    }
+
+    /// Adds a soft constraint, which adds its weight to the score of every bin for which it
+    /// returns `true`.
+    ///
+    /// Unlike a filter, this ranks matching bins higher without ruling any bin out, such as bins
+    /// in the zone the item prefers.
+    pub fn prefer(
+        self,
+        name: &'static str,
+        weight: f64,
+        f: impl Fn(&Candidate) -> bool + 'static,
+    ) -> Self {
+        self.score(name, weight, move |candidate| {
+            f64::from(u8::from(f(candidate)))
+        })
+    }
+
+    /// Evaluates every bin for the item, returning where it would go and why.
+    pub fn decide(&self, bins: &[impl Bin], item: &impl Item) -> Decision {
+        let verdicts = bins
+            .iter()
+            .enumerate()
+            .map(|(index, bin)| {
+                let candidate = Candidate {
+                    index,
+                    capacity: capacity(bins),
+                    load: bin.load(),
+                    group: bin.group(),
+                    item,
+                };
+                self.rate(bin, &candidate)
+            })
+            .collect::<Vec<_>>();
+        let mut bin: Option<(usize, f64)> = None;
+        for (index, verdict) in verdicts.iter().enumerate() {
+            if let Verdict::Scored { total, .. } = *verdict {
+                if bin.is_none_or(|(_, best)| total > best) {
+                    bin = Some((index, total));
+                }
+            }
+        }
+        Decision {
+            bin: bin.map(|(index, _)| index),
+            verdicts,
+        }
+    }
+
+    /// Rates a bin through the filters and scores.
+    fn rate(&self, bin: &impl Bin, candidate: &Candidate) -> Verdict {
+        if !bin.fits(&candidate.item.size()) {
+            return Verdict::Rejected("capacity");
+        }
+        if let Some((name, _)) = self.filters.iter().find(|(_, f)| !f(candidate)) {
+            return Verdict::Rejected(name);
+        }
+        let scores = self
+            .scores
+            .iter()
+            .map(|(name, weight, f)| (*name, weight * f(candidate)))
+            .collect::<Vec<_>>();
+        Verdict::Scored {
+            total: scores.iter().map(|(_, score)| score).sum(),
+            scores,
+        }
+    }
+}
+
+impl Strategy for Pipeline {
+    fn next_idx(&self, bins: &[impl Bin], item: &impl Item) -> Option<usize> {
+        self.decide(bins, item).bin
+    }
+    fn explain(&self, bins: &[impl Bin], item: &impl Item) -> Vec<Verdict> {
+        self.decide(bins, item).verdicts
+    }
+}
+
+/// Where a [`Pipeline`] places an item, and how it rated each bin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    /// The bin chosen, or `None` if the item needs a new bin.
+    pub bin: Option<usize>,
+    /// The verdict on each bin, by bin index.
+    pub verdicts: Vec<Verdict>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn pipeline_filters_then_scores() {
+        let bins = [3, 6, 8, 5].map(|used| BinImpl { used });
+        assert_eq!(Pipeline::new().next_idx(&bins, &2), Some(0));
+
+        let fullest = Pipeline::new().score("fullest", 1.0, fuller);
+        assert_eq!(fullest.next_idx(&bins, &2), Some(2));
+        assert_eq!(fullest.next_idx(&bins, &3), Some(1));
+
+        let odd = fullest.filter("odd", |c| c.index % 2 == 1);
+        assert_eq!(odd.next_idx(&bins, &2), Some(1));
+        assert_eq!(odd.next_idx(&bins, &6), None);
+    }
+
+    #[test]
+    fn decisions_report_soft_constraints() {
+        let bins = [3, 6, 8].map(|used| BinImpl { used });
+        let zones = [0, 1, 1];
+        let pipeline =
+            Pipeline::new()
+                .score("emptier", 1.0, emptier)
+                .prefer("same zone", 0.5, move |c| zones[c.index] == 1);
+        let decision = pipeline.decide(&bins, &3);
+        assert_eq!(decision.bin, Some(1));
+        assert_eq!(decision.verdicts[2], Verdict::Rejected("capacity"));
+        let Verdict::Scored { total, scores } = &decision.verdicts[1] else {
+            panic!("bin 1 was rejected");
+        };
+        assert!((total - 0.6).abs() < 1e-9);
+        assert_eq!(scores[1], ("same zone", 0.5));
+    }
+}
@@ -0,0 +1,286 @@
+//! Capacity planning: how much packing changes with the number and capacity of bins.
+
+use crate::*;
+
+/// How the value packed into a fixed number of bins responds to more capacity, see
+/// [`sensitivity`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sensitivity {
+    /// The total value packed into the bins.
+    pub value: usize,
+    /// The total value packed with one more bin.
+    pub extra_bin: usize,
+    /// The total value packed with one of the bins grown by the given amount.
+    pub larger_bin: usize,
+    /// The indices of the items that do not fit into the bins.
+    pub leftovers: Vec<usize>,
+    /// The indices of the leftovers that one more bin would take, which are the items the number
+    /// of bins holds back.
+    pub binding: Vec<usize>,
+}
+
+/// Packs the most valuable items into `bins` bins of the given capacity, and works out how much
+/// more value one more bin, or one bin larger by `growth`, would hold.
+///
+/// The items are packed like [`offline::pack_most_valuable`], so the values are those of its
+/// greedy packing rather than of the best possible one.
+pub fn sensitivity(
+    capacity: usize,
+    bins: usize,
+    growth: usize,
+    items: &[impl Item],
+) -> Sensitivity {
+    let (value, leftovers) = most_valuable(&vec![capacity; bins], items);
+    let (extra_bin, unpacked) = most_valuable(&vec![capacity; bins + 1], items);
+    let mut capacities = vec![capacity; bins];
+    if let Some(first) = capacities.first_mut() {
+        *first = first.saturating_add(growth);
+    }
+    let (larger_bin, _) = most_valuable(&capacities, items);
+    let binding = leftovers
+        .iter()
+        .copied()
+        .filter(|i| !unpacked.contains(i))
+        .collect();
+    Sensitivity {
+        value,
+        extra_bin,
+        larger_bin,
+        leftovers,
+        binding,
+    }
+}
+
+/// How the planning functions count the bins that items need.
+#[derive(Clone, Copy, Debug)]
+pub enum Solver {
+    /// Packs with a built-in strategy, which is fast but may use more bins than necessary.
+    Heuristic(Algorithm),
+    /// Searches for the fewest bins, settling for the best packing found if it hits its node
+    /// limit first.
+    Exact(exact::BranchAndBound),
+}
+
+impl Solver {
+    /// Returns the number of bins of the given capacity the items are packed into.
+    pub fn bins(&self, capacity: usize, items: &[impl Item]) -> usize {
+        match self {
+            Solver::Heuristic(algorithm) => algorithm.pack(capacity, items).len(),
+            Solver::Exact(search) => search.solve_capacity(capacity, items).bins,
+        }
+    }
+}
+
+/// Returns the smallest capacity at which the solver packs the items into at most `k` bins, or
+/// `None` if there are items but no bins.
+///
+/// The capacity is found by binary search. Heuristics can need more bins at a larger capacity, so
+/// with [`Solver::Heuristic`] the capacity returned fits, but a smaller one may fit as well.
+pub fn min_capacity_for(solver: Solver, items: &[impl Item], k: usize) -> Option<usize> {
+    if k == 0 {
+        return items.is_empty().then_some(0);
+    }
+    let largest = items.iter().map(Item::size).max().unwrap_or(0);
+    let total = items.iter().map(Item::size).sum::<usize>();
+    let mut low = largest.max(total.div_ceil(k));
+    // All items fit into a single bin of their total size.
+    let mut high = largest.max(total);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if solver.bins(mid, items) <= k {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Some(low)
+}
+
+/// The number of bins items need at one capacity, a point of the curve returned by [`tradeoff`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityPoint {
+    /// The capacity of the bins.
+    pub capacity: usize,
+    /// The number of bins the solver packs the items into.
+    pub bins: usize,
+}
+
+/// Counts the bins the solver packs the items into at each of the capacities, in the order given,
+/// to weigh larger bins against needing fewer of them.
+pub fn tradeoff(
+    solver: Solver,
+    items: &[impl Item],
+    capacities: impl IntoIterator<Item = usize>,
+) -> Vec<CapacityPoint> {
+    capacities
+        .into_iter()
+        .map(|capacity| CapacityPoint {
+            capacity,
+            bins: solver.bins(capacity, items),
+        })
+        .collect()
+}
+
+/// Whether items fit into a number of bins, see [`fits_in`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feasibility {
+    /// The items fit.
+    Fits,
+    /// The items are proven not to fit.
+    DoesNotFit,
+    /// Neither could be shown.
+    Unknown,
+}
+
+/// Returns whether the items fit into `k` bins of the given capacity, without building a packing
+/// where it can be avoided.
+///
+/// The lower bounds of [`bounds`] rule out too few bins, and a first fit decreasing packing shows
+/// that enough bins fit. Where neither settles it, the answer is [`Feasibility::Unknown`], which
+/// [`fits_in_exact`] resolves.
+pub fn fits_in(items: &[impl Item], capacity: usize, k: usize) -> Feasibility {
+    if items.iter().any(|item| item.size() > capacity)
+        || (k == 0 && !items.is_empty())
+        || bounds::l2(capacity, items) > k
+    {
+        Feasibility::DoesNotFit
+    } else if Algorithm::FirstFitDecreasing.pack(capacity, items).len() <= k {
+        Feasibility::Fits
+    } else {
+        Feasibility::Unknown
+    }
+}
+
+/// Returns whether the items fit into `k` bins of the given capacity like [`fits_in`], searching
+/// for a packing where the quick checks do not settle it.
+///
+/// The answer is only [`Feasibility::Unknown`] if the search hits its node limit first.
+pub fn fits_in_exact(
+    items: &[impl Item],
+    capacity: usize,
+    k: usize,
+    search: exact::BranchAndBound,
+) -> Feasibility {
+    match fits_in(items, capacity, k) {
+        Feasibility::Unknown => {
+            let solution = search.solve_capacity(capacity, items);
+            if solution.bins <= k {
+                Feasibility::Fits
+            } else if solution.lower_bound > k {
+                Feasibility::DoesNotFit
+            } else {
+                Feasibility::Unknown
+            }
+        }
+        answer => answer,
+    }
+}
+
+/// Packs the most valuable items into bins of the given capacities, returning the value packed
+/// and the indices of the items left over, in order.
+fn most_valuable(capacities: &[usize], items: &[impl Item]) -> (usize, Vec<usize>) {
+    let mut bins = capacities
+        .iter()
+        .map(|&available| Space { available })
+        .collect::<Vec<_>>();
+    let mut indexed = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| Indexed {
+            index,
+            size: item.size(),
+            value: item.value(),
+        })
+        .collect();
+    let value = offline::pack_most_valuable(&mut bins, &mut indexed);
+    let mut leftovers = indexed.iter().map(|item| item.index).collect::<Vec<_>>();
+    leftovers.sort_unstable();
+    (value, leftovers)
+}
+
+/// A bin with its own capacity, as far as is left of it.
+#[derive(Default)]
+struct Space {
+    available: usize,
+}
+
+impl Bin for Space {
+    fn capacity() -> usize {
+        usize::MAX
+    }
+    fn available(&self) -> usize {
+        self.available
+    }
+    fn pack(&mut self, item: impl Item) {
+        self.available -= item.size();
+    }
+    fn fits(&self, item: &impl Item) -> bool {
+        item.size() <= self.available
+    }
+}
+
+/// An item that remembers its index.
+struct Indexed {
+    index: usize,
+    size: usize,
+    value: usize,
+}
+
+impl Item for Indexed {
+    fn size(&self) -> usize {
+        self.size
+    }
+    fn value(&self) -> usize {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitivity_values_more_capacity() {
+        let sizes = [6, 5, 4, 3];
+        let sensitivity = sensitivity(10, 1, 2, &sizes);
+        assert_eq!(sensitivity.value, 10);
+        assert_eq!(sensitivity.extra_bin, 18);
+        assert_eq!(sensitivity.larger_bin, 11);
+        assert_eq!(sensitivity.leftovers, [1, 3]);
+        assert_eq!(sensitivity.binding, [1, 3]);
+    }
+
+    #[test]
+    fn min_capacity_fits_into_the_bins() {
+        let sizes = [5, 4, 3, 3, 3, 2];
+        let ffd = Solver::Heuristic(Algorithm::FirstFitDecreasing);
+        let exact = Solver::Exact(exact::BranchAndBound::default());
+        assert_eq!(min_capacity_for(ffd, &sizes, 2), Some(11));
+        assert_eq!(min_capacity_for(exact, &sizes, 2), Some(10));
+        assert_eq!(min_capacity_for(exact, &sizes, 6), Some(5));
+        assert_eq!(min_capacity_for(exact, &sizes, 0), None);
+        assert_eq!(min_capacity_for(exact, &[0; 0], 0), Some(0));
+    }
+
+    #[test]
+    fn tradeoff_counts_bins_per_capacity() {
+        let solver = Solver::Exact(exact::BranchAndBound::default());
+        let curve = tradeoff(solver, &[5, 4, 3, 3, 3, 2], (5..=20).step_by(5));
+        let bins = curve.iter().map(|point| point.bins).collect::<Vec<_>>();
+        assert_eq!(bins, [5, 2, 2, 1]);
+        assert_eq!(curve[1].capacity, 10);
+    }
+
+    #[test]
+    fn fits_in_settles_what_it_can() {
+        let sizes = [5, 4, 3, 3, 3, 2];
+        assert_eq!(fits_in(&sizes, 11, 2), Feasibility::Fits);
+        assert_eq!(fits_in(&sizes, 10, 1), Feasibility::DoesNotFit);
+        assert_eq!(fits_in(&sizes, 4, 9), Feasibility::DoesNotFit);
+        assert_eq!(fits_in(&sizes, 10, 2), Feasibility::Unknown);
+        let search = exact::BranchAndBound::default();
+        assert_eq!(fits_in_exact(&sizes, 10, 2, search), Feasibility::Fits);
+        assert_eq!(fits_in_exact(&[0; 0], 10, 0, search), Feasibility::Fits);
+        assert_eq!(fits_in_exact(&[0], 10, 0, search), Feasibility::DoesNotFit);
+    }
+}
@@ -0,0 +1,136 @@
+//! Packing into several pools of bins in order of priority, such as machines on premises before
+//! those in the cloud, or reserved instances before spot ones.
+
+use std::cmp::Reverse;
+
+use crate::Item;
+
+/// A pool of bins of the same capacity, of which [`pack`] opens as many as needed up to a limit.
+///
+/// Pools describe their bins by capacity alone rather than by a [`crate::Bin`] type, so pools of
+/// different kinds of bins fit into one slice and the packing is recorded as item indices, like a
+/// [`crate::Packing`]. For bins with a tolerance, include it in the capacity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool {
+    /// The capacity of each bin in the pool.
+    pub capacity: usize,
+    /// The maximum number of bins to open in the pool, or `None` if there is no limit.
+    pub limit: Option<usize>,
+}
+
+/// How much of a pool a packing uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// The number of bins opened in the pool.
+    pub bins: usize,
+    /// The total size of the items in the bins of the pool.
+    pub load: usize,
+    /// The total capacity of the bins opened in the pool.
+    pub capacity: usize,
+}
+
+/// Items packed into pools of bins by [`pack`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pooled {
+    /// The indices of the items in each bin of each pool, in the order of the pools.
+    pub pools: Vec<Vec<Vec<usize>>>,
+    /// How much of each pool is used, in the order of the pools.
+    pub usage: Vec<Usage>,
+    /// The indices of the items that fit into no pool, in order.
+    pub leftovers: Vec<usize>,
+}
+
+/// Packs the items largest first into pools of bins, preferring pools earlier in the slice.
+///
+/// Each item goes into the first open bin it fits into, trying the pools in order, and otherwise
+/// into a new bin of the first pool that has bins left and fits the item. A pool is thus filled
+/// before bins are opened in the next one. Items that fit into no pool are left over.
+pub fn pack(pools: &[Pool], items: &[impl Item]) -> Pooled {
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(items[i].size()));
+
+    let mut bins = vec![Vec::<Vec<usize>>::new(); pools.len()];
+    let mut loads = vec![Vec::<usize>::new(); pools.len()];
+    let mut leftovers = vec![];
+    for i in order {
+        let size = items[i].size();
+        let open = (0..pools.len()).find_map(|p| {
+            let b = loads[p]
+                .iter()
+                .position(|&load| load + size <= pools[p].capacity)?;
+            Some((p, b))
+        });
+        let new = || {
+            pools.iter().enumerate().find_map(|(p, pool)| {
+                let room = pool.limit.is_none_or(|limit| bins[p].len() < limit);
+                (room && size <= pool.capacity).then_some((p, bins[p].len()))
+            })
+        };
+        let Some((p, b)) = open.or_else(new) else {
+            leftovers.push(i);
+            continue;
+        };
+        if b == bins[p].len() {
+            bins[p].push(vec![]);
+            loads[p].push(0);
+        }
+        bins[p][b].push(i);
+        loads[p][b] += size;
+    }
+    leftovers.sort_unstable();
+
+    let usage = pools
+        .iter()
+        .zip(&loads)
+        .map(|(pool, loads)| Usage {
+            bins: loads.len(),
+            load: loads.iter().sum(),
+            capacity: loads.len() * pool.capacity,
+        })
+        .collect();
+    Pooled {
+        pools: bins,
+        usage,
+        leftovers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earlier_pools_fill_first() {
+        let pools = [
+            Pool {
+                capacity: 10,
+                limit: Some(1),
+            },
+            Pool {
+                capacity: 8,
+                limit: None,
+            },
+        ];
+        let pooled = pack(&pools, &[6, 5, 4, 3, 9, 12]);
+        assert_eq!(
+            pooled.pools,
+            [vec![vec![4]], vec![vec![0], vec![1, 3], vec![2]]]
+        );
+        assert_eq!(
+            pooled.usage,
+            [
+                Usage {
+                    bins: 1,
+                    load: 9,
+                    capacity: 10
+                },
+                Usage {
+                    bins: 3,
+                    load: 18,
+                    capacity: 24
+                }
+            ]
+        );
+        assert_eq!(pooled.leftovers, [5]);
+    }
+}
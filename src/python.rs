@@ -0,0 +1,86 @@
+//! Python bindings through PyO3, enabled by the `python` feature.
+//!
+//! Build the extension module with `maturin build`, which picks up the features configured in
+//! `pyproject.toml`, builds the library as a cdylib, and provides a `bin_packing` module to import.
+
+// The `#[pyfunction]` expansion converts errors into `PyErr` even when they already are one.
+#![allow(clippy::useless_conversion)]
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{bounds, Algorithm, Packing};
+
+/// Parses the strategy name and packs the items, raising `ValueError` on bad input.
+fn pack_with(sizes: &[usize], capacity: usize, strategy: &str) -> PyResult<Packing> {
+    let algorithm = strategy
+        .parse::<Algorithm>()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    if let Some(size) = sizes.iter().find(|&&size| size > capacity) {
+        return Err(PyValueError::new_err(format!(
+            "item of size {size} exceeds capacity {capacity}"
+        )));
+    }
+    Ok(algorithm.pack(capacity, sizes))
+}
+
+/// Packs items into bins using the named strategy and returns the index of the bin each item is
+/// packed into.
+#[pyfunction]
+#[pyo3(signature = (sizes, capacity, strategy = "ffd"))]
+fn pack(sizes: Vec<usize>, capacity: usize, strategy: &str) -> PyResult<Vec<usize>> {
+    Ok(pack_with(&sizes, capacity, strategy)?.assignment())
+}
+
+/// Packs items with each of the named strategies, or all built-in ones, and returns the number of
+/// bins each needs.
+#[pyfunction]
+#[pyo3(signature = (sizes, capacity, strategies = None))]
+fn compare(
+    sizes: Vec<usize>,
+    capacity: usize,
+    strategies: Option<Vec<String>>,
+) -> PyResult<HashMap<String, usize>> {
+    let strategies = strategies.unwrap_or_else(|| {
+        Algorithm::ALL
+            .iter()
+            .map(|algorithm| algorithm.name().to_string())
+            .collect()
+    });
+    strategies
+        .into_iter()
+        .map(|strategy| {
+            let bins = pack_with(&sizes, capacity, &strategy)?.len();
+            Ok((strategy, bins))
+        })
+        .collect()
+}
+
+/// Packs items using the named strategy and returns statistics about the packing: the number of
+/// bins, the lower bound on that number, and the utilization of the bins.
+#[pyfunction]
+#[pyo3(signature = (sizes, capacity, strategy = "ffd"))]
+fn stats(
+    py: Python<'_>,
+    sizes: Vec<usize>,
+    capacity: usize,
+    strategy: &str,
+) -> PyResult<HashMap<&'static str, PyObject>> {
+    let packing = pack_with(&sizes, capacity, strategy)?;
+    Ok(HashMap::from([
+        ("bins", packing.len().into_py(py)),
+        ("lower_bound", bounds::l1(capacity, &sizes).into_py(py)),
+        ("utilization", packing.utilization().into_py(py)),
+        ("loads", packing.loads().into_py(py)),
+    ]))
+}
+
+#[pymodule]
+fn bin_packing(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(pack, m)?)?;
+    m.add_function(wrap_pyfunction!(compare, m)?)?;
+    m.add_function(wrap_pyfunction!(stats, m)?)?;
+    Ok(())
+}
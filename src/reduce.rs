@@ -0,0 +1,284 @@
+//! Preprocessing that shrinks instances before packing them.
+//!
+//! Each reduction returns a smaller or simpler instance along with what is needed to map a
+//! packing of it back onto the original items.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::eval::Instance;
+use crate::*;
+
+/// An instance whose sizes and capacity were divided by a common factor, created with
+/// [`normalize`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scaled {
+    /// The scaled down instance, with the items in their original order.
+    pub instance: Instance,
+    /// The factor the sizes were divided by.
+    pub scale: usize,
+}
+
+impl Scaled {
+    /// Returns the original size of a size or load of the scaled instance.
+    pub fn unscale(&self, size: usize) -> usize {
+        size * self.scale
+    }
+}
+
+/// Divides the sizes of the items and the capacity by the greatest common divisor of the sizes.
+///
+/// Every load is a multiple of the divisor, so rounding the scaled capacity down loses no
+/// packing, and a packing of the scaled instance packs the original items into the same bins.
+/// This shrinks the numbers that methods whose work grows with the capacity have to handle.
+pub fn normalize(capacity: usize, items: &[impl Item]) -> Scaled {
+    let scale = items
+        .iter()
+        .fold(0, |divisor, item| gcd(divisor, item.size()))
+        .max(1);
+    Scaled {
+        instance: Instance {
+            capacity: capacity / scale,
+            sizes: items.iter().map(|item| item.size() / scale).collect(),
+        },
+        scale,
+    }
+}
+
+/// Items whose sizes add up to exactly the capacity, matched up by [`complements`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Complements {
+    /// The indices of each pair of items that fill a bin exactly.
+    pub pairs: Vec<(usize, usize)>,
+    /// The indices of all other items, in order.
+    pub rest: Vec<usize>,
+}
+
+/// Matches up as many pairs of items whose sizes add up to exactly the capacity as possible.
+///
+/// Some optimal packing puts each such pair into a bin of its own, so packing the pairs that way
+/// and only the rest with a strategy never costs a bin, and leaves a smaller instance for it.
+pub fn complements(capacity: usize, items: &[impl Item]) -> Complements {
+    let mut waiting = HashMap::<usize, Vec<usize>>::new();
+    let mut pairs = vec![];
+    for (i, item) in items.iter().enumerate() {
+        let Some(complement) = capacity.checked_sub(item.size()) else {
+            continue;
+        };
+        match waiting.get_mut(&complement).and_then(Vec::pop) {
+            Some(j) => pairs.push((j, i)),
+            None => waiting.entry(item.size()).or_default().push(i),
+        }
+    }
+    let mut paired = vec![false; items.len()];
+    for &(a, b) in &pairs {
+        paired[a] = true;
+        paired[b] = true;
+    }
+    Complements {
+        pairs,
+        rest: (0..items.len()).filter(|&i| !paired[i]).collect(),
+    }
+}
+
+/// An offline strategy that packs each pair of items that fills a bin exactly into a new bin, see
+/// [`complements`], and the remaining items with another strategy.
+pub struct PairComplements<S>(pub S);
+
+impl<S> PairComplements<S> {
+    /// Packs the pairs into new bins and returns the remaining items.
+    fn pair<B: Bin, I: Item>(&self, bins: &mut Vec<B>, items: &mut Vec<I>) -> Vec<I> {
+        let found = complements(B::capacity(), items);
+        let max = B::default().max_item_size();
+        let mut items = items.drain(..).map(Some).collect::<Vec<_>>();
+        for (a, b) in found.pairs {
+            let sizes = [a, b].map(|i| items[i].as_ref().map_or(0, Item::size));
+            if sizes.iter().any(|&size| size > max) {
+                continue;
+            }
+            let mut bin = B::default();
+            for i in [a, b] {
+                bin.pack(items[i].take().unwrap());
+            }
+            bins.push(bin);
+        }
+        items.into_iter().flatten().collect()
+    }
+}
+
+impl<S: offline::Strategy> offline::Strategy for PairComplements<S> {
+    fn pack_all<B: Bin>(&self, bins: &mut Vec<B>, items: &mut Vec<impl Item>) {
+        let mut rest = self.pair(bins, items);
+        self.0.pack_all(bins, &mut rest);
+    }
+
+    fn pack_all_until<B: Bin>(
+        &self,
+        bins: &mut Vec<B>,
+        items: &mut Vec<impl Item>,
+        deadline: Instant,
+    ) -> bool {
+        let mut rest = self.pair(bins, items);
+        self.0.pack_all_until(bins, &mut rest, deadline)
+    }
+}
+
+/// Bins that some optimal packing contains, fixed by [`dominated_bins`], and the items left over.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reduction {
+    /// The indices of the items in each fixed bin.
+    pub bins: Vec<Vec<usize>>,
+    /// The indices of all other items, in order.
+    pub rest: Vec<usize>,
+}
+
+/// Fixes bins that dominate every other way to pack their largest item, following the reduction
+/// procedure of Martello and Toth.
+///
+/// Going through the items largest first, a set of items that fits into a bin dominates all others
+/// containing the same item if the items of any other set can be grouped so that each group is at
+/// most as large as a different item of the set. Some optimal packing then contains the set as a
+/// bin, so it is fixed and taken out of the instance. Like the original procedure, this only
+/// considers sets of up to three items, so it is quick but does not find every dominating set.
+pub fn dominated_bins(capacity: usize, items: &[impl Item]) -> Reduction {
+    let size = |i: usize| items[i].size();
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(size(i)));
+    let mut fixed = vec![false; items.len()];
+    let mut bins = vec![];
+    for &j in &order {
+        if fixed[j] {
+            continue;
+        }
+        // The other items that are still free, largest first.
+        let others = order
+            .iter()
+            .copied()
+            .filter(|&i| i != j && !fixed[i])
+            .collect::<Vec<_>>();
+        let Some(room) = capacity.checked_sub(size(j)) else {
+            fixed[j] = true;
+            bins.push(vec![j]);
+            continue;
+        };
+        let smallest = |count: usize| {
+            (others.len() >= count).then(|| {
+                others[others.len() - count..]
+                    .iter()
+                    .map(|&i| size(i))
+                    .sum()
+            })
+        };
+        let fit = |count| smallest(count).is_some_and(|total: usize| total <= room);
+
+        let bin = match others.iter().position(|&i| size(i) <= room) {
+            None => Some(vec![j]),
+            // The largest item that fits covers any single item, or everything that fits if it
+            // fills the bin.
+            Some(k) if size(others[k]) == room || !fit(2) => Some(vec![j, others[k]]),
+            // With at most two more items, the larger of any pair is covered by the largest item
+            // that fits, and the smaller, which is at most half the room, by the largest item that
+            // fits next to it.
+            Some(k) if !fit(3) => {
+                let rest = room - size(others[k]);
+                let second = others[k + 1..].iter().copied().find(|&i| size(i) <= rest);
+                let half = others.iter().map(|&i| size(i)).find(|&s| s <= room / 2);
+                second
+                    .filter(|&b| half.is_none_or(|half| half <= size(b)))
+                    .map(|b| vec![j, others[k], b])
+            }
+            Some(_) => None,
+        };
+        if let Some(bin) = bin {
+            for &i in &bin {
+                fixed[i] = true;
+            }
+            bins.push(bin);
+        }
+    }
+    Reduction {
+        bins,
+        rest: (0..items.len()).filter(|&i| !fixed[i]).collect(),
+    }
+}
+
+/// Returns the greatest common divisor of two numbers, which is zero only if both are.
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::Strategy;
+
+    #[derive(Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity().saturating_sub(self.used)
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn normalize_divides_by_the_common_divisor() {
+        let scaled = normalize(105, &[20, 30, 50, 0]);
+        assert_eq!(scaled.scale, 10);
+        assert_eq!(scaled.instance.capacity, 10);
+        assert_eq!(scaled.instance.sizes, [2, 3, 5, 0]);
+        assert_eq!(scaled.unscale(7), 70);
+        let packing = Algorithm::FirstFitDecreasing.pack(10, &scaled.instance.sizes);
+        let original = Algorithm::FirstFitDecreasing.pack(105, &[20, 30, 50, 0]);
+        assert_eq!(packing.bins(), original.bins());
+
+        assert_eq!(normalize(10, &[0, 0]).scale, 1);
+    }
+
+    #[test]
+    fn complements_fill_bins_exactly() {
+        let sizes = [3, 7, 5, 7, 5, 5, 12, 4];
+        let found = complements(10, &sizes);
+        assert_eq!(found.pairs, [(0, 1), (2, 4)]);
+        assert_eq!(found.rest, [3, 5, 6, 7]);
+
+        let mut bins = Vec::<BinImpl>::new();
+        let mut items = sizes.to_vec();
+        PairComplements(offline::FirstFitDecreasing).pack_all(&mut bins, &mut items);
+        assert!(items.is_empty());
+        let loads = bins.iter().map(|bin| bin.used).collect::<Vec<_>>();
+        assert_eq!(loads, [10, 10, 12, 7, 9]);
+    }
+
+    #[test]
+    fn dominated_bins_are_fixed() {
+        let reduction = dominated_bins(10, &[9, 6, 4, 5, 5, 3, 3, 2, 1, 12, 7, 2]);
+        assert_eq!(
+            reduction.bins,
+            [
+                vec![9],
+                vec![0, 8],
+                vec![10, 5],
+                vec![1, 2],
+                vec![3, 4],
+                vec![6, 7, 11]
+            ]
+        );
+        assert!(reduction.rest.is_empty());
+
+        let reduction = dominated_bins(100, &[49, 30, 28, 26, 20, 45, 44]);
+        assert_eq!(reduction.bins, [vec![4, 0, 1]]);
+        assert_eq!(reduction.rest, [2, 3, 5, 6]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Packing results that keep everything a strategy computed.
+
+use std::fmt;
+
+use crate::*;
+
+/// The result of packing items into bins, which keeps track of where each item went alongside
+/// the bins themselves.
+///
+/// Items larger than the bin capacity, plus its [`Bin::tolerance`], or than the
+/// [`Bin::max_item_size`] of every bin cannot be packed by any strategy, so they are set aside as
+/// leftovers instead.
+#[derive(Debug)]
+pub struct PackingResult<B, I> {
+    /// The bins, starting with any that were passed in.
+    pub bins: Vec<B>,
+    /// The index of the bin each item is packed into, by item index, or `None` for leftovers.
+    pub assignment: Vec<Option<usize>>,
+    /// The items that could not be packed, in their original order.
+    pub leftovers: Vec<I>,
+    /// Statistics about the bins, whose utilization counts the [`Bin::tolerance`] as capacity, so
+    /// it is at most one.
+    pub stats: Stats,
+    /// A lower bound on the number of bins needed to hold the packed items.
+    pub lower_bound: usize,
+    /// How long each phase of the strategy took, if created with [`PackingResult::profiled`].
+    pub phases: Vec<offline::Phase>,
+}
+
+/// Statistics about a set of bins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    /// The number of bins.
+    pub bins: usize,
+    /// The number of items packed.
+    pub items: usize,
+    /// The total size of everything in the bins.
+    pub load: usize,
+    /// The fraction of the total capacity of all bins that is taken up by items.
+    pub utilization: f64,
+}
+
+impl<B: Bin, I: Item> PackingResult<B, I> {
+    /// Packs items into the given bins using an offline strategy, creating new bins as needed.
+    pub fn new(
+        strategy: &impl offline::Strategy,
+        bins: Vec<B>,
+        items: impl IntoIterator<Item = I>,
+    ) -> Self {
+        Self::build(strategy, bins, items, false)
+    }
+
+    /// Packs items like [`PackingResult::new`], also recording how long each phase of the
+    /// strategy took.
+    pub fn profiled(
+        strategy: &impl offline::Strategy,
+        bins: Vec<B>,
+        items: impl IntoIterator<Item = I>,
+    ) -> Self {
+        Self::build(strategy, bins, items, true)
+    }
+
+    fn build(
+        strategy: &impl offline::Strategy,
+        mut bins: Vec<B>,
+        items: impl IntoIterator<Item = I>,
+        profile: bool,
+    ) -> Self {
+        let limits = offline::Limits::of(&bins);
+        let limit = limits.capacity;
+        let initial = bins.iter().map(Bin::load).collect::<Vec<_>>();
+
+        let mut assignment = vec![];
+        let mut packed = vec![];
+        let mut indices = vec![];
+        let mut leftovers = vec![];
+        for (i, item) in items.into_iter().enumerate() {
+            assignment.push(None);
+            if limits.accepts(item.size()) {
+                packed.push(item);
+                indices.push(i);
+            } else {
+                leftovers.push(item);
+            }
+        }
+        let sizes = packed.iter().map(Item::size).collect::<Vec<_>>();
+        let (placed, phases) = packing::assign(&limits, &initial, &packed, |bins, items| {
+            if profile {
+                strategy.pack_all_profiled(bins, items)
+            } else {
+                strategy.pack_all(bins, items);
+                vec![]
+            }
+        });
+        for (&i, &bin) in indices.iter().zip(&placed) {
+            assignment[i] = Some(bin);
+        }
+        offline::place(&mut bins, &mut packed, &placed);
+
+        let load = initial.iter().sum::<usize>() + sizes.iter().sum::<usize>();
+        // What the bins already hold has to end up in some bin as well, like an item would.
+        let contents = initial.iter().chain(&sizes).copied().collect::<Vec<_>>();
+        let lower_bound = initial.len().max(bounds::l2(limit, &contents));
+        let total = bins.len() * limit;
+        let stats = Stats {
+            bins: bins.len(),
+            items: sizes.len(),
+            load,
+            utilization: if total == 0 {
+                0.0
+            } else {
+                load as f64 / total as f64
+            },
+        };
+        Self {
+            bins,
+            assignment,
+            leftovers,
+            stats,
+            lower_bound,
+            phases,
+        }
+    }
+}
+
+impl<B: Bin, I> fmt::Display for PackingResult<B, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let capacity = B::capacity();
+        let loads = self.bins.iter().map(Bin::load).collect::<Vec<_>>();
+        table(f, capacity, &loads, None)?;
+        write!(f, "{}, lower bound {}", self.stats, self.lower_bound)?;
+        match self.leftovers.len() {
+            0 => {}
+            1 => write!(f, ", 1 leftover")?,
+            n => write!(f, ", {n} leftovers")?,
+        }
+        for phase in &self.phases {
+            write!(f, "\n{phase}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bins, {} items, load {}, utilization {:.1}%",
+            self.bins,
+            self.items,
+            self.load,
+            self.utilization * 100.0
+        )
+    }
+}
+
+/// Writes a table with a row for each bin, showing its load, its utilization, and the items in it
+/// if known.
+pub(crate) fn table(
+    f: &mut fmt::Formatter<'_>,
+    capacity: usize,
+    loads: &[usize],
+    items: Option<&[Vec<usize>]>,
+) -> fmt::Result {
+    let index = loads.len().saturating_sub(1).to_string().len().max(3);
+    let load = format!("{capacity}/{capacity}").len().max(4);
+    write!(f, "{:>index$}  {:>load$}  utilization", "bin", "load")?;
+    if items.is_some() {
+        f.write_str("  items")?;
+    }
+    writeln!(f)?;
+    for (i, &used) in loads.iter().enumerate() {
+        let utilization = if capacity == 0 {
+            0.0
+        } else {
+            used as f64 / capacity as f64 * 100.0
+        };
+        write!(
+            f,
+            "{i:>index$}  {:>load$}  {utilization:>10.1}%",
+            format!("{used}/{capacity}")
+        )?;
+        if let Some(items) = items {
+            let items = items[i].iter().map(usize::to_string).collect::<Vec<_>>();
+            write!(f, "  {}", items.join(" "))?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::FirstFitDecreasing;
+
+    #[derive(Debug, Default)]
+    struct BinImpl {
+        used: usize,
+    }
+    impl Bin for BinImpl {
+        fn capacity() -> usize {
+            10
+        }
+        fn available(&self) -> usize {
+            Self::capacity() - self.used
+        }
+        fn pack(&mut self, item: impl Item) {
+            self.used += item.size();
+        }
+    }
+
+    #[test]
+    fn lower_bound_counts_large_items() {
+        let result = PackingResult::new(&FirstFitDecreasing, Vec::<BinImpl>::new(), vec![6, 6, 6]);
+        assert_eq!(result.lower_bound, 3);
+
+        #[derive(Default)]
+        struct Empty;
+        impl Bin for Empty {
+            fn capacity() -> usize {
+                0
+            }
+            fn available(&self) -> usize {
+                0
+            }
+            fn pack(&mut self, _: impl Item) {}
+        }
+        let result = PackingResult::new(&FirstFitDecreasing, Vec::<Empty>::new(), vec![0, 0]);
+        assert_eq!(result.stats.bins, 1);
+        assert_eq!(result.stats.utilization, 0.0);
+    }
+
+    #[test]
+    fn keeps_assignment_and_leftovers() {
+        let result = PackingResult::new(
+            &FirstFitDecreasing,
+            vec![BinImpl { used: 5 }],
+            vec![4, 12, 6, 5],
+        );
+        assert_eq!(result.assignment, vec![Some(1), None, Some(1), Some(0)]);
+        assert_eq!(result.leftovers, vec![12]);
+        assert_eq!(
+            result.bins.iter().map(|b| b.used).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+        assert_eq!(
+            result.stats,
+            Stats {
+                bins: 2,
+                items: 3,
+                load: 20,
+                utilization: 1.0,
+            }
+        );
+        assert_eq!(result.lower_bound, 2);
+        assert_eq!(
+            result.to_string(),
+            "bin   load  utilization
+  0  10/10       100.0%
+  1  10/10       100.0%
+2 bins, 3 items, load 20, utilization 100.0%, lower bound 2, 1 leftover"
+        );
+        assert!(result.phases.is_empty());
+
+        let result = PackingResult::profiled(
+            &offline::ModifiedFirstFitDecreasing,
+            vec![BinImpl::default()],
+            vec![6, 4, 4, 3, 2, 1],
+        );
+        assert_eq!(
+            result
+                .phases
+                .iter()
+                .map(|phase| (phase.name, phase.count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("large", 1),
+                ("medium", 1),
+                ("small", 0),
+                ("fill", 0),
+                ("remainder", 4)
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Packing into plain assignments, for callers that apply the packing elsewhere.
+//!
+//! The functions here return where each item goes and the load of each bin, without any [`Bin`]
+//! type. [`pack_sizes`] also takes the sizes as plain integers, and runs the fit strategies
+//! directly on them, skipping the trait machinery for the most throughput.
+
+use std::cmp::Reverse;
+
+use crate::online::PackExt;
+use crate::*;
+
+/// Where items were packed, as the bin of each item and the load of each bin.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Assignment {
+    /// The index of the bin each item is packed into, in item order.
+    pub bins: Vec<usize>,
+    /// The total size of the items packed into each bin.
+    pub loads: Vec<u64>,
+}
+
+impl Assignment {
+    /// Creates an assignment from the bin of each item, adding up the loads from their sizes.
+    fn from_bins(bins: Vec<usize>, sizes: impl IntoIterator<Item = u64>) -> Self {
+        let mut loads = vec![0; bins.iter().max().map_or(0, |&bin| bin + 1)];
+        for (&bin, size) in bins.iter().zip(sizes) {
+            loads[bin] += size;
+        }
+        Self { bins, loads }
+    }
+
+    /// Returns the number of bins used.
+    pub fn len(&self) -> usize {
+        self.loads.len()
+    }
+
+    /// Returns `true` if there are no bins.
+    pub fn is_empty(&self) -> bool {
+        self.loads.is_empty()
+    }
+}
+
+impl From<&Packing> for Assignment {
+    fn from(packing: &Packing) -> Self {
+        Self {
+            bins: packing.assignment(),
+            loads: packing
+                .loads()
+                .into_iter()
+                .map(|load| load as u64)
+                .collect(),
+        }
+    }
+}
+
+/// Packs items into bins of the given capacity using an offline strategy, and returns where they
+/// went.
+pub fn assign(
+    strategy: impl offline::Strategy,
+    capacity: usize,
+    items: &[impl Item],
+) -> Assignment {
+    let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+    let limits = offline::Limits::capacity(capacity);
+    let (bins, ()) = packing::assign(&limits, &[], items, |bins, items| {
+        strategy.pack_all(bins, items)
+    });
+    Assignment::from_bins(bins, sizes.into_iter().map(|size| size as u64))
+}
+
+/// Packs items into bins of the given capacity using an online strategy, in order, and returns
+/// where they went.
+pub fn assign_online(
+    strategy: impl online::Strategy,
+    capacity: usize,
+    items: &[impl Item],
+) -> Assignment {
+    let sizes = items.iter().map(Item::size).collect::<Vec<_>>();
+    let bins = sizes.iter().copied().pack_assignments(strategy, capacity);
+    Assignment::from_bins(bins, sizes.into_iter().map(|size| size as u64))
+}
+
+/// Packs items of the given sizes into bins of the given capacity using a built-in strategy.
+///
+/// The first, next, best and worst fit strategies and their decreasing variants run directly on
+/// the sizes. All other strategies are packed with their regular implementation, so their sizes
+/// need to fit into a `usize`. Items larger than the capacity get a bin of their own.
+pub fn pack_sizes(algorithm: Algorithm, capacity: u64, sizes: &[u64]) -> Assignment {
+    let Some((fit, decreasing)) = Fit::of(algorithm) else {
+        let usize = |n: u64| usize::try_from(n).expect("size exceeds usize");
+        let sizes = sizes.iter().map(|&size| usize(size)).collect::<Vec<_>>();
+        return Assignment::from(&algorithm.pack(usize(capacity), &sizes));
+    };
+    let mut assignment = Assignment {
+        bins: vec![0; sizes.len()],
+        loads: vec![],
+    };
+    for i in order(sizes, decreasing) {
+        let size = sizes[i];
+        let loads = &mut assignment.loads;
+        let bin = fit.choose(loads, capacity, size).unwrap_or_else(|| {
+            loads.push(0);
+            loads.len() - 1
+        });
+        loads[bin] += size;
+        assignment.bins[i] = bin;
+    }
+    #[cfg(feature = "cross-check")]
+    if let (Ok(capacity), Some(sizes)) = (
+        usize::try_from(capacity),
+        sizes
+            .iter()
+            .map(|&size| usize::try_from(size).ok())
+            .collect::<Option<Vec<_>>>(),
+    ) {
+        let loads = algorithm.pack(capacity, &sizes).loads();
+        assert!(
+            loads
+                .into_iter()
+                .map(|load| load as u64)
+                .eq(assignment.loads.iter().copied()),
+            "{algorithm} on plain sizes disagrees with its regular implementation"
+        );
+    }
+    assignment
+}
+
+/// Packs items like [`pack_sizes`], but places each run of equally sized items that are packed
+/// one after another in bulk.
+///
+/// The decreasing strategies pack all items of the same size in one run, so on items with few
+/// distinct sizes this makes one placement per bin and size rather than one per item. First, next
+/// and best fit and their decreasing variants place each run exactly where [`pack_sizes`] places
+/// its items one by one, while all other strategies are packed with [`pack_sizes`].
+pub fn pack_sizes_aggregated(algorithm: Algorithm, capacity: u64, sizes: &[u64]) -> Assignment {
+    let Some((fit, decreasing)) = Fit::of(algorithm).filter(|&(fit, _)| fit != Fit::Worst) else {
+        return pack_sizes(algorithm, capacity, sizes);
+    };
+    let mut assignment = Assignment {
+        bins: vec![0; sizes.len()],
+        loads: vec![],
+    };
+    for mut run in order(sizes, decreasing).chunk_by(|&a, &b| sizes[a] == sizes[b]) {
+        let size = sizes[run[0]];
+        while !run.is_empty() {
+            let loads = &mut assignment.loads;
+            let bin = fit.choose(loads, capacity, size).unwrap_or_else(|| {
+                loads.push(0);
+                loads.len() - 1
+            });
+            // Packing an item leaves the bin as good a choice for the next one as before, so the
+            // run goes into it until it is full.
+            let room = capacity.saturating_sub(loads[bin]);
+            let count = match room.checked_div(size) {
+                Some(count) => count.clamp(1, run.len() as u64) as usize,
+                None => run.len(),
+            };
+            let (placed, rest) = run.split_at(count);
+            loads[bin] += size * count as u64;
+            for &i in placed {
+                assignment.bins[i] = bin;
+            }
+            run = rest;
+        }
+    }
+    #[cfg(feature = "cross-check")]
+    assert_eq!(
+        assignment,
+        pack_sizes(algorithm, capacity, sizes),
+        "{algorithm} on runs disagrees with packing items one by one"
+    );
+    assignment
+}
+
+/// Returns the order to pack sizes in, which is decreasing size if requested and item order
+/// otherwise.
+fn order(sizes: &[u64], decreasing: bool) -> Vec<usize> {
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    if decreasing {
+        order.sort_by_key(|&i| Reverse(sizes[i]));
+    }
+    order
+}
+
+/// Which bin the fit strategies choose among the bins an item fits into.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fit {
+    First,
+    Next,
+    Best,
+    Worst,
+}
+
+impl Fit {
+    /// Returns how a built-in strategy chooses bins and whether it packs items in decreasing
+    /// order, or `None` if it is not a fit strategy.
+    fn of(algorithm: Algorithm) -> Option<(Self, bool)> {
+        Some(match algorithm {
+            Algorithm::FirstFit => (Fit::First, false),
+            Algorithm::NextFit => (Fit::Next, false),
+            Algorithm::BestFit => (Fit::Best, false),
+            Algorithm::WorstFit => (Fit::Worst, false),
+            Algorithm::FirstFitDecreasing => (Fit::First, true),
+            Algorithm::BestFitDecreasing => (Fit::Best, true),
+            Algorithm::WorstFitDecreasing => (Fit::Worst, true),
+            _ => return None,
+        })
+    }
+
+    /// Returns the bin to pack an item of the given size into, or `None` if it needs a new bin.
+    fn choose(self, loads: &[u64], capacity: u64, size: u64) -> Option<usize> {
+        let fits = |load: u64| load.checked_add(size).is_some_and(|load| load <= capacity);
+        let mut candidates = loads.iter().enumerate().filter(|&(_, &load)| fits(load));
+        match self {
+            Fit::First => candidates.next().map(|(i, _)| i),
+            Fit::Next => loads
+                .last()
+                .filter(|&&load| fits(load))
+                .map(|_| loads.len() - 1),
+            // The least available capacity is the greatest load, preferring the first on ties.
+            Fit::Best => candidates
+                .min_by_key(|&(_, &load)| Reverse(load))
+                .map(|(i, _)| i),
+            Fit::Worst => candidates.min_by_key(|&(_, &load)| load).map(|(i, _)| i),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_sizes_matches_the_regular_strategies() {
+        let sizes = [4u64, 7, 2, 9, 3, 3, 5, 1, 6, 2, 8, 4];
+        let usizes = sizes.map(|size| size as usize);
+        for algorithm in Algorithm::ALL {
+            let packing = algorithm.pack(10, &usizes);
+            let assignment = pack_sizes(algorithm, 10, &sizes);
+            let mut loads = vec![0; assignment.len()];
+            for (&size, &bin) in sizes.iter().zip(&assignment.bins) {
+                loads[bin] += size;
+            }
+            assert_eq!(loads, assignment.loads, "{algorithm}");
+            let expected = packing.loads().into_iter().map(|load| load as u64);
+            assert!(loads.into_iter().eq(expected), "{algorithm}");
+        }
+        assert_eq!(pack_sizes(Algorithm::FirstFit, 5, &[7, 2]).loads, [7, 2]);
+    }
+
+    #[test]
+    fn aggregated_runs_pack_like_single_items() {
+        let mut sizes = [3u64, 3, 3, 5, 5, 2, 2, 2, 2, 10, 10, 0, 7, 3, 3].repeat(3);
+        sizes.extend([4; 10]);
+        for algorithm in Algorithm::ALL {
+            assert_eq!(
+                pack_sizes_aggregated(algorithm, 10, &sizes),
+                pack_sizes(algorithm, 10, &sizes),
+                "{algorithm}"
+            );
+        }
+    }
+
+    #[test]
+    fn assignments_need_no_bin_type() {
+        let sizes = [2, 3, 8, 7];
+        let assignment = assign(offline::FirstFitDecreasing, 10, &sizes);
+        assert_eq!(assignment.bins, [0, 1, 0, 1]);
+        assert_eq!(assignment.loads, [10, 10]);
+        let assignment = assign_online(online::FirstFit, 10, &sizes);
+        assert_eq!(assignment.bins, [0, 0, 1, 2]);
+        assert_eq!(assignment.loads, [5, 8, 7]);
+        let solution = exact::BranchAndBound::default().solve_capacity(10, &sizes);
+        assert_eq!(solution.bins, 2);
+    }
+}
@@ -0,0 +1,133 @@
+//! Packing as well as possible within a deadline, escalating from quick heuristics to an exact
+//! search for as long as time allows.
+
+use std::fmt;
+use std::time::Instant;
+
+use crate::eval::Instance;
+use crate::*;
+
+/// The stage of [`solve_best_by`] that found a packing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// [`Algorithm::FirstFitDecreasing`].
+    FirstFitDecreasing,
+    /// [`Algorithm::ModifiedFirstFitDecreasing`].
+    ModifiedFirstFitDecreasing,
+    /// The local search of [`metaheuristics::Grasp`].
+    LocalSearch,
+    /// The search of [`exact::BranchAndBound`].
+    Exact,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::FirstFitDecreasing => "ffd",
+            Stage::ModifiedFirstFitDecreasing => "mffd",
+            Stage::LocalSearch => "local search",
+            Stage::Exact => "exact",
+        })
+    }
+}
+
+/// The best packing [`solve_best_by`] found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Best {
+    /// The packing.
+    pub packing: Packing,
+    /// The stage that found the packing.
+    pub stage: Stage,
+    /// Whether the packing is proven to use the fewest bins possible.
+    pub optimal: bool,
+}
+
+/// Packs the instance with first fit decreasing, then modified first fit decreasing, a local
+/// search and finally an exact search, keeping the packing with the fewest bins.
+///
+/// Each stage only runs while the deadline has not passed, and the search stops as soon as a
+/// packing meets the [`bounds::l2`] lower bound. The local search gets at most half of the time
+/// left, and the exact search starts from the best packing so far. First fit decreasing always
+/// runs, so there is a packing even if the deadline has already passed.
+pub fn solve_best_by(instance: &Instance, deadline: Instant) -> Best {
+    let Instance { capacity, sizes } = instance;
+    let lower_bound = bounds::l2(*capacity, sizes);
+    let mut best = Best {
+        packing: Algorithm::FirstFitDecreasing.pack(*capacity, sizes),
+        stage: Stage::FirstFitDecreasing,
+        optimal: false,
+    };
+    let done = |best: &Best| best.packing.len() <= lower_bound || Instant::now() >= deadline;
+    let improve = |best: &mut Best, packing: Packing, stage| {
+        if packing.len() < best.packing.len() {
+            *best = Best {
+                packing,
+                stage,
+                optimal: false,
+            };
+        }
+    };
+
+    if !done(&best) {
+        let packing = Algorithm::ModifiedFirstFitDecreasing.pack(*capacity, sizes);
+        improve(&mut best, packing, Stage::ModifiedFirstFitDecreasing);
+    }
+    if !done(&best) {
+        let now = Instant::now();
+        let half = now + deadline.saturating_duration_since(now) / 2;
+        let grasp = metaheuristics::Grasp {
+            restarts: 100,
+            alpha: 0.3,
+            seed: 0,
+        };
+        let (packing, _) = Packing::new_until(&grasp, *capacity, sizes, half);
+        improve(&mut best, packing, Stage::LocalSearch);
+    }
+    if !done(&best) {
+        let solution = exact::BranchAndBound::default().improve_until(&best.packing, deadline);
+        if solution.bins < best.packing.len() {
+            let mut bins = vec![vec![]; solution.bins];
+            for (i, &bin) in solution.assignment.iter().enumerate() {
+                bins[bin].push(i);
+            }
+            let packing = Packing::relabeled(*capacity, sizes.clone(), bins);
+            improve(&mut best, packing, Stage::Exact);
+        }
+        best.optimal = solution.is_optimal() && solution.bins == best.packing.len();
+    }
+    best.optimal |= best.packing.len() <= lower_bound;
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn escalates_until_optimal() {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let easy = Instance {
+            capacity: 10,
+            sizes: vec![5, 5, 4, 6],
+        };
+        let best = solve_best_by(&easy, deadline);
+        assert_eq!(best.stage, Stage::FirstFitDecreasing);
+        assert_eq!(best.packing.len(), 2);
+        assert!(best.optimal);
+
+        // First fit decreasing needs three bins, the others find two.
+        let tricky = Instance {
+            capacity: 10,
+            sizes: vec![4, 4, 3, 3, 3, 3],
+        };
+        let best = solve_best_by(&tricky, deadline);
+        assert_ne!(best.stage, Stage::FirstFitDecreasing);
+        assert_eq!(best.packing.len(), 2);
+        assert!(best.optimal);
+
+        let best = solve_best_by(&tricky, Instant::now());
+        assert_eq!(best.stage, Stage::FirstFitDecreasing);
+        assert!(!best.optimal);
+    }
+}
@@ -0,0 +1,174 @@
+//! Newtypes for item sizes and bin capacities.
+//!
+//! Everything else in this crate measures sizes in plain `usize`s, which are easily mixed up with
+//! item counts and bin indices. Code that interfaces with the crate can use these types instead
+//! and convert at the boundary, since [`Size`] is an [`Item`] and both convert to and from `usize`.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+use crate::*;
+
+/// The size of an item, or the total size of several items.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(pub usize);
+
+/// The capacity of a bin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Capacity(pub usize);
+
+impl Size {
+    /// Returns the size of an item.
+    pub fn of(item: &impl Item) -> Self {
+        Self(item.size())
+    }
+}
+
+impl Capacity {
+    /// Returns the capacity of bins of type `B`.
+    pub fn of<B: Bin>() -> Self {
+        Self(B::capacity())
+    }
+
+    /// Returns whether items of the given total size fit within the capacity.
+    pub fn fits(self, size: Size) -> bool {
+        size.0 <= self.0
+    }
+
+    /// Returns how much of the capacity is left after the given load, or zero if the load
+    /// exceeds it.
+    pub fn remaining(self, load: Size) -> Size {
+        Size(self.0.saturating_sub(load.0))
+    }
+}
+
+impl Item for Size {
+    fn size(&self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for Size {
+    fn from(size: usize) -> Self {
+        Self(size)
+    }
+}
+
+impl From<Size> for usize {
+    fn from(size: Size) -> Self {
+        size.0
+    }
+}
+
+impl From<usize> for Capacity {
+    fn from(capacity: usize) -> Self {
+        Self(capacity)
+    }
+}
+
+impl From<Capacity> for usize {
+    fn from(capacity: Capacity) -> Self {
+        capacity.0
+    }
+}
+
+impl Add for Size {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Size {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Size {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Size {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+/// Scales a size by a count, such as the total size of several items of the same size.
+impl Mul<usize> for Size {
+    type Output = Self;
+
+    fn mul(self, count: usize) -> Self {
+        Self(self.0 * count)
+    }
+}
+
+impl Sum for Size {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|size| size.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a Size> for Size {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// The total capacity of several bins of the same capacity.
+impl Mul<usize> for Capacity {
+    type Output = Self;
+
+    fn mul(self, count: usize) -> Self {
+        Self(self.0 * count)
+    }
+}
+
+/// What is left of the capacity after a load, which panics if the load exceeds it, see
+/// [`Capacity::remaining`] for a saturating alternative.
+impl Sub<Size> for Capacity {
+    type Output = Size;
+
+    fn sub(self, load: Size) -> Size {
+        Size(self.0 - load.0)
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Capacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_pack_like_plain_numbers() {
+        let sizes = [Size(6), Size(4), Size(5)];
+        let total = sizes.iter().sum::<Size>();
+        assert_eq!(total, Size(15));
+        let capacity = Capacity(10);
+        assert!(!capacity.fits(total));
+        assert_eq!(capacity - Size(6), Size(4));
+        assert_eq!(capacity.remaining(total), Size(0));
+        assert_eq!(usize::from(capacity * 2), 20);
+
+        let packing = Algorithm::NextFit.pack(capacity.into(), &sizes);
+        assert_eq!(packing.bins(), &[vec![0, 1], vec![2]]);
+    }
+}
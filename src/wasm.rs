@@ -0,0 +1,26 @@
+//! JavaScript bindings for WebAssembly builds, enabled by the `wasm` feature.
+//!
+//! The library builds as an rlib by default, so build the WebAssembly module as a cdylib with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type
+//! cdylib`, and generate the JavaScript glue with `wasm-bindgen`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Algorithm;
+
+/// Packs items of the given sizes into bins of the given capacity using the named strategy,
+/// returning the index of the bin each item is packed into.
+///
+/// Strategies are named as in [`Algorithm::name`], for example `ffd`.
+#[wasm_bindgen]
+pub fn pack(sizes: &[u32], capacity: u32, strategy: &str) -> Result<Vec<u32>, JsError> {
+    let algorithm = strategy.parse::<Algorithm>()?;
+    if let Some(size) = sizes.iter().find(|&&size| size > capacity) {
+        return Err(JsError::new(&format!(
+            "item of size {size} exceeds capacity {capacity}"
+        )));
+    }
+    let sizes = sizes.iter().map(|&size| size as usize).collect::<Vec<_>>();
+    let packing = algorithm.pack(capacity as usize, &sizes);
+    Ok(packing.assignment().into_iter().map(|b| b as u32).collect())
+}
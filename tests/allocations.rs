@@ -0,0 +1,57 @@
+//! Checks that packing into caller-provided storage makes no allocations.
+//!
+//! This lives in its own test binary so that the counting allocator only replaces the allocator
+//! of these tests.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use bin_packing::online::{pack_in_place, BestFit};
+use bin_packing::{Bin, Item};
+
+/// Counts the allocations made on each thread, so tests can check that packing makes none.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Default)]
+struct BinImpl {
+    used: usize,
+}
+impl Bin for BinImpl {
+    fn capacity() -> usize {
+        10
+    }
+    fn available(&self) -> usize {
+        Self::capacity() - self.used
+    }
+    fn pack(&mut self, item: impl Item) {
+        self.used += item.size();
+    }
+}
+
+#[test]
+fn pack_in_place_does_not_allocate() {
+    let mut bins: [BinImpl; 2] = Default::default();
+    let mut len = 0;
+    let before = ALLOCATIONS.with(Cell::get);
+    let placed = [6, 5, 4, 3, 7].map(|size| pack_in_place(BestFit, &mut bins, &mut len, size));
+    assert_eq!(ALLOCATIONS.with(Cell::get), before);
+    assert_eq!(placed, [Ok(0), Ok(1), Ok(0), Ok(1), Err(7)]);
+    assert_eq!(len, 2);
+}